@@ -1,14 +1,16 @@
+use super::credentials::{parse_credentials, CredentialSource};
 use crate::constants::{GOOGLE_DOCS_API_URL, GOOGLE_DOCS_SCOPE, GOOGLE_TOKEN_URL, JWT_EXPIRATION_SECS};
 use crate::models::{
-    BatchUpdateRequest, BatchUpdateResponse, Document, GoogleDocsRequest,
-    ServiceAccountCredentials, TokenResponse,
+    BatchUpdateRequest, BatchUpdateResponse, CreateDocumentRequest, Document, GoogleDocsRequest,
+    Credentials, ServiceAccountCredentials, TokenResponse, WriteControl,
 };
 use chrono::Utc;
 use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use reqwest::Client;
 use rmcp::ErrorData as McpError;
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tokio::sync::RwLock;
 
@@ -25,6 +27,9 @@ struct JwtClaims {
     iat: i64,
     /// Expiration timestamp
     exp: i64,
+    /// User to impersonate via domain-wide delegation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sub: Option<String>,
 }
 
 /// Cached access token with expiration
@@ -34,17 +39,42 @@ struct CachedToken {
     expires_at: i64,
 }
 
-/// Google Docs API client with Service Account authentication
+/// Number of recently fetched documents a client remembers per reader
+const MAX_RECENT_DOCUMENTS: usize = 50;
+
+/// Number of readers whose recent documents a client remembers
+const MAX_RECENT_READERS: usize = 256;
+
+/// A document fetched through a client
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentDocument {
+    pub document_id: String,
+    pub title: String,
+}
+
+/// Recent documents of each reader, most recently active reader first
+type RecentDocumentsByReader = VecDeque<(String, VecDeque<RecentDocument>)>;
+
+/// Google Docs API client with Service Account or user authentication
 #[derive(Clone)]
 pub struct GoogleDocsClient {
     client: Client,
-    credentials: ServiceAccountCredentials,
+    credentials: Credentials,
+    subject: Option<String>,
+    scopes: Vec<String>,
     cached_token: Arc<RwLock<Option<CachedToken>>>,
+    recent_documents: Arc<Mutex<RecentDocumentsByReader>>,
+    reader: String,
 }
 
 impl GoogleDocsClient {
     /// Create a new Google Docs API client from service account credentials
     pub fn new(credentials: ServiceAccountCredentials) -> Self {
+        Self::from_credentials(Credentials::ServiceAccount(credentials))
+    }
+
+    /// Create a new Google Docs API client from any supported credentials
+    pub fn from_credentials(credentials: Credentials) -> Self {
         let client = Client::builder()
             .timeout(Duration::from_secs(30))
             .build()
@@ -53,32 +83,81 @@ impl GoogleDocsClient {
         Self {
             client,
             credentials,
+            subject: None,
+            scopes: vec![GOOGLE_DOCS_SCOPE.to_string()],
             cached_token: Arc::new(RwLock::new(None)),
+            recent_documents: Arc::new(Mutex::new(VecDeque::new())),
+            reader: String::new(),
         }
     }
 
+    /// Request a different set of OAuth scopes for access tokens.
+    ///
+    /// Any cached token is discarded so the next request uses the new scopes.
+    pub fn with_scopes(mut self, scopes: Vec<String>) -> Self {
+        self.scopes = scopes;
+        self.cached_token = Arc::new(RwLock::new(None));
+        self
+    }
+
+    /// The OAuth scopes requested for access tokens
+    pub fn scopes(&self) -> &[String] {
+        &self.scopes
+    }
+
+    /// Impersonate a Workspace user through domain-wide delegation.
+    ///
+    /// Only service account credentials can impersonate; the returned client
+    /// has its own token cache and recent documents.
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Result<Self, McpError> {
+        if !matches!(self.credentials, Credentials::ServiceAccount(_)) {
+            return Err(McpError::invalid_params(
+                "Impersonating a subject requires service account credentials".to_string(),
+                None,
+            ));
+        }
+        self.subject = Some(subject.into());
+        self.cached_token = Arc::new(RwLock::new(None));
+        self.recent_documents = Arc::new(Mutex::new(VecDeque::new()));
+        Ok(self)
+    }
+
+    /// A handle on this client that remembers the documents it fetches for
+    /// `reader` apart from those fetched for other readers
+    pub fn for_reader(&self, reader: impl Into<String>) -> Self {
+        Self {
+            reader: reader.into(),
+            ..self.clone()
+        }
+    }
+
+    /// The Workspace user this client impersonates, if any
+    pub fn subject(&self) -> Option<&str> {
+        self.subject.as_deref()
+    }
+
+    /// The credentials this client authenticates with
+    pub fn credentials(&self) -> &Credentials {
+        &self.credentials
+    }
+
     /// Create a new client from a JSON key file path
     pub fn from_json_file(path: &str) -> Result<Self, McpError> {
-        let content = std::fs::read_to_string(path).map_err(|e| {
-            McpError::internal_error(
-                format!("Failed to read service account key file: {}", e),
-                None,
-            )
-        })?;
+        Self::from_source(&CredentialSource::File(path.into()))
+    }
 
-        let credentials: ServiceAccountCredentials =
-            serde_json::from_str(&content).map_err(|e| {
-                McpError::internal_error(
-                    format!("Failed to parse service account key file: {}", e),
-                    None,
-                )
-            })?;
+    /// Create a new client from the key JSON itself (plain or base64-encoded)
+    pub fn from_json_str(content: &str) -> Result<Self, McpError> {
+        Ok(Self::from_credentials(parse_credentials(content)?))
+    }
 
-        Ok(Self::new(credentials))
+    /// Create a new client from any supported credential source
+    pub fn from_source(source: &CredentialSource) -> Result<Self, McpError> {
+        Ok(Self::from_credentials(source.load()?))
     }
 
     /// Get a valid access token, refreshing if necessary
-    async fn get_access_token(&self) -> Result<String, McpError> {
+    pub(crate) async fn get_access_token(&self) -> Result<String, McpError> {
         // Check if we have a valid cached token
         {
             let cached = self.cached_token.read().await;
@@ -103,21 +182,47 @@ impl GoogleDocsClient {
         Ok(new_token.access_token)
     }
 
-    /// Fetch a new access token using Service Account JWT
+    /// Fetch a new access token for the configured credentials
     async fn fetch_new_token(&self) -> Result<CachedToken, McpError> {
+        match &self.credentials {
+            Credentials::ServiceAccount(credentials) => {
+                self.fetch_service_account_token(credentials).await
+            }
+            Credentials::AuthorizedUser(credentials) => {
+                let scope = self.scopes.join(" ");
+                let params = [
+                    ("grant_type", "refresh_token"),
+                    ("client_id", credentials.client_id.as_str()),
+                    ("client_secret", credentials.client_secret.as_str()),
+                    ("refresh_token", credentials.refresh_token.as_str()),
+                    // Narrows the token to the scopes this server needs
+                    ("scope", scope.as_str()),
+                ];
+                let token_url = credentials.token_uri.as_deref().unwrap_or(GOOGLE_TOKEN_URL);
+                self.exchange_token(token_url, &params).await
+            }
+        }
+    }
+
+    /// Fetch a new access token using Service Account JWT
+    async fn fetch_service_account_token(
+        &self,
+        credentials: &ServiceAccountCredentials,
+    ) -> Result<CachedToken, McpError> {
         let now = Utc::now().timestamp();
         let exp = now + JWT_EXPIRATION_SECS;
 
         let claims = JwtClaims {
-            iss: self.credentials.client_email.clone(),
-            scope: GOOGLE_DOCS_SCOPE.to_string(),
+            iss: credentials.client_email.clone(),
+            scope: self.scopes.join(" "),
             aud: GOOGLE_TOKEN_URL.to_string(),
             iat: now,
             exp,
+            sub: self.subject.clone(),
         };
 
         let header = Header::new(Algorithm::RS256);
-        let key = EncodingKey::from_rsa_pem(self.credentials.private_key.as_bytes())
+        let key = EncodingKey::from_rsa_pem(credentials.private_key.as_bytes())
             .map_err(|e| {
                 McpError::internal_error(format!("Failed to parse private key: {}", e), None)
             })?;
@@ -132,13 +237,24 @@ impl GoogleDocsClient {
             ("assertion", &jwt),
         ];
 
+        self.exchange_token(GOOGLE_TOKEN_URL, &params).await
+    }
+
+    /// Exchange a grant at the token endpoint for an access token
+    async fn exchange_token(
+        &self,
+        token_url: &str,
+        params: &[(&str, &str)],
+    ) -> Result<CachedToken, McpError> {
+        let now = Utc::now().timestamp();
+
         let response = self
             .client
-            .post(GOOGLE_TOKEN_URL)
-            .form(&params)
+            .post(token_url)
+            .form(params)
             .send()
             .await
-            .map_err(|e| handle_api_error(e))?;
+            .map_err(handle_api_error)?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -162,8 +278,42 @@ impl GoogleDocsClient {
         })
     }
 
+    /// The underlying HTTP client, shared with the Drive API client
+    pub(crate) fn http(&self) -> &Client {
+        &self.client
+    }
+
+    /// Create an empty Google Document with the given title
+    pub async fn create_document(&self, title: &str) -> Result<Document, McpError> {
+        let token = self.get_access_token().await?;
+
+        let response = self
+            .client
+            .post(format!("{}/documents", GOOGLE_DOCS_API_URL))
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&CreateDocumentRequest {
+                title: title.to_string(),
+            })
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
     /// Get a Google Document by ID
     pub async fn get_document(&self, document_id: &str) -> Result<Document, McpError> {
+        let document = self.get_document_json(document_id).await?;
+        serde_json::from_value(document).map_err(|e| {
+            McpError::internal_error(format!("Failed to parse API response: {}", e), None)
+        })
+    }
+
+    /// Get a Google Document by ID as the raw JSON returned by the API
+    pub async fn get_document_json(
+        &self,
+        document_id: &str,
+    ) -> Result<serde_json::Value, McpError> {
         let token = self.get_access_token().await?;
 
         // Request document with tabs content structure (newer Google Docs format).
@@ -179,7 +329,64 @@ impl GoogleDocsClient {
             .await
             .map_err(handle_api_error)?;
 
-        handle_response(response).await
+        let document: serde_json::Value = handle_response(response).await?;
+        if let Some(title) = document["title"].as_str() {
+            self.remember_document(document_id, title);
+        }
+        Ok(document)
+    }
+
+    /// Get only the current revision ID of a Google Document
+    pub async fn get_revision_id(&self, document_id: &str) -> Result<Option<String>, McpError> {
+        let token = self.get_access_token().await?;
+
+        let response = self
+            .client
+            .get(format!(
+                "{}/documents/{}?fields=revisionId",
+                GOOGLE_DOCS_API_URL, document_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        let document: serde_json::Value = handle_response(response).await?;
+        Ok(document["revisionId"].as_str().map(str::to_string))
+    }
+
+    /// Documents most recently fetched through this client for its reader,
+    /// newest first
+    pub fn recent_documents(&self) -> Vec<RecentDocument> {
+        self.recent_documents
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .iter()
+            .find(|(reader, _)| *reader == self.reader)
+            .map(|(_, documents)| documents.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Move a document to the front of the reader's recent documents
+    fn remember_document(&self, document_id: &str, title: &str) {
+        let mut readers = self
+            .recent_documents
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let mut recent = readers
+            .iter()
+            .position(|(reader, _)| *reader == self.reader)
+            .and_then(|position| readers.remove(position))
+            .map(|(_, documents)| documents)
+            .unwrap_or_default();
+        recent.retain(|document| document.document_id != document_id);
+        recent.push_front(RecentDocument {
+            document_id: document_id.to_string(),
+            title: title.to_string(),
+        });
+        recent.truncate(MAX_RECENT_DOCUMENTS);
+        readers.push_front((self.reader.clone(), recent));
+        readers.truncate(MAX_RECENT_READERS);
     }
 
     /// Update a Google Document with batch requests
@@ -187,10 +394,16 @@ impl GoogleDocsClient {
         &self,
         document_id: &str,
         requests: Vec<GoogleDocsRequest>,
+        required_revision_id: Option<&str>,
     ) -> Result<BatchUpdateResponse, McpError> {
         let token = self.get_access_token().await?;
 
-        let request_body = BatchUpdateRequest { requests };
+        let request_body = BatchUpdateRequest {
+            requests,
+            write_control: required_revision_id.map(|revision_id| WriteControl {
+                required_revision_id: revision_id.to_string(),
+            }),
+        };
 
         let response = self
             .client
@@ -210,9 +423,18 @@ impl GoogleDocsClient {
 }
 
 /// Handle API response and convert to result
-async fn handle_response<T: serde::de::DeserializeOwned>(
+pub(crate) async fn handle_response<T: serde::de::DeserializeOwned>(
     response: reqwest::Response,
 ) -> Result<T, McpError> {
+    let response = check_status(response).await?;
+
+    response.json().await.map_err(|e| {
+        McpError::internal_error(format!("Failed to parse API response: {}", e), None)
+    })
+}
+
+/// Convert an unsuccessful API response into an error
+pub(crate) async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, McpError> {
     let status = response.status();
 
     if !status.is_success() {
@@ -241,9 +463,7 @@ async fn handle_response<T: serde::de::DeserializeOwned>(
         });
     }
 
-    response.json().await.map_err(|e| {
-        McpError::internal_error(format!("Failed to parse API response: {}", e), None)
-    })
+    Ok(response)
 }
 
 /// Convert reqwest errors to MCP errors with clear messages
@@ -259,3 +479,34 @@ pub fn handle_api_error(error: reqwest::Error) -> McpError {
         McpError::internal_error(format!("Network error: {}", error), None)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_JSON: &str = include_str!("../../tests/fixtures/service-account.json");
+
+    #[test]
+    fn recent_documents_are_kept_per_reader() {
+        // Given: One client handed to two readers
+        let client = GoogleDocsClient::from_json_str(KEY_JSON).unwrap();
+        let alice = client.for_reader("identity:alice");
+        let bob = client.for_reader("identity:bob");
+
+        // When: Each reader fetches a document
+        alice.remember_document("doc-a", "Alice's notes");
+        bob.remember_document("doc-b", "Bob's notes");
+
+        // Then: Each should only see their own document
+        let ids = |client: &GoogleDocsClient| -> Vec<String> {
+            client
+                .recent_documents()
+                .into_iter()
+                .map(|document| document.document_id)
+                .collect()
+        };
+        assert_eq!(ids(&alice), ["doc-a"]);
+        assert_eq!(ids(&bob), ["doc-b"]);
+        assert!(client.recent_documents().is_empty());
+    }
+}
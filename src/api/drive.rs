@@ -0,0 +1,604 @@
+use super::client::{GoogleDocsClient, check_status, handle_api_error, handle_response};
+use crate::constants::{GOOGLE_DOCS_MIME_TYPE, GOOGLE_DRIVE_API_URL};
+use crate::models::{
+    Comment, CommentList, CommentReply, DriveFile, DriveFileList, ExportFormat, Permission,
+    PermissionList, Revision, RevisionList,
+};
+use rmcp::ErrorData as McpError;
+
+/// Metadata fields requested for each file in search results
+const SEARCH_FIELDS: &str = "nextPageToken,files(id,name,mimeType,webViewLink,modifiedTime,owners(displayName,emailAddress))";
+
+/// Fields requested for each comment and its replies
+const COMMENT_FIELDS: &str = "id,content,author(displayName,emailAddress),createdTime,resolved,\
+     quotedFileContent,replies(id,content,author(displayName,emailAddress),createdTime,action)";
+
+/// Fields requested for a reply
+const REPLY_FIELDS: &str = "id,content,author(displayName,emailAddress),createdTime,action";
+
+/// Upper bound on comment pages fetched for one document
+const MAX_COMMENT_PAGES: usize = 20;
+
+/// Fields requested for each revision
+const REVISION_FIELDS: &str =
+    "id,modifiedTime,lastModifyingUser(displayName,emailAddress),exportLinks";
+
+/// Upper bound on revision pages fetched for one document
+const MAX_REVISION_PAGES: usize = 20;
+
+/// Fields requested for each permission
+const PERMISSION_FIELDS: &str = "id,type,role,emailAddress,domain,displayName";
+
+/// Upper bound on permission pages fetched for one file
+const MAX_PERMISSION_PAGES: usize = 20;
+
+/// Items collected from a paged Drive listing, up to a cap on the pages fetched
+#[derive(Debug, Clone, Default)]
+pub struct Listing<T> {
+    /// The items, in the order Drive returned them
+    pub items: Vec<T>,
+    /// Whether the cap was reached with pages left, so later items are missing
+    pub truncated: bool,
+}
+
+/// Criteria for finding Google Docs in Drive; unset criteria match everything
+#[derive(Debug, Clone, Default)]
+pub struct DriveSearch {
+    /// Substring of the document title
+    pub name: Option<String>,
+    /// Words or phrases in the document content or title
+    pub full_text: Option<String>,
+    /// Email address of an owner
+    pub owner: Option<String>,
+    /// ID of the folder directly containing the document
+    pub folder_id: Option<String>,
+    /// Only documents modified at or after this RFC 3339 time
+    pub modified_after: Option<String>,
+    /// Only documents modified before this RFC 3339 time
+    pub modified_before: Option<String>,
+    /// Search this shared drive instead of the user's files
+    pub shared_drive_id: Option<String>,
+    /// Maximum number of results per page
+    pub page_size: u32,
+    /// Token from a previous page
+    pub page_token: Option<String>,
+}
+
+impl DriveSearch {
+    /// The Drive `q` expression for these criteria
+    pub fn query(&self) -> String {
+        let mut clauses = vec![
+            format!("mimeType = '{}'", GOOGLE_DOCS_MIME_TYPE),
+            "trashed = false".to_string(),
+        ];
+        if let Some(ref name) = self.name {
+            clauses.push(format!("name contains '{}'", escape_query_value(name)));
+        }
+        if let Some(ref text) = self.full_text {
+            clauses.push(format!("fullText contains '{}'", escape_query_value(text)));
+        }
+        if let Some(ref owner) = self.owner {
+            clauses.push(format!("'{}' in owners", escape_query_value(owner)));
+        }
+        if let Some(ref folder_id) = self.folder_id {
+            clauses.push(format!("'{}' in parents", escape_query_value(folder_id)));
+        }
+        if let Some(ref after) = self.modified_after {
+            clauses.push(format!("modifiedTime >= '{}'", escape_query_value(after)));
+        }
+        if let Some(ref before) = self.modified_before {
+            clauses.push(format!("modifiedTime < '{}'", escape_query_value(before)));
+        }
+        clauses.join(" and ")
+    }
+}
+
+/// Escape a string literal for a Drive query
+fn escape_query_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// Google Drive API client sharing credentials and the token cache of a
+/// [`GoogleDocsClient`].
+///
+/// Drive calls need the scopes of the matching [`crate::DriveFeature`]s,
+/// which the server requests when those features are enabled.
+#[derive(Clone, Copy)]
+pub struct DriveClient<'a> {
+    docs: &'a GoogleDocsClient,
+}
+
+impl GoogleDocsClient {
+    /// A Drive API client authenticating as this client
+    pub fn drive(&self) -> DriveClient<'_> {
+        DriveClient { docs: self }
+    }
+}
+
+impl DriveClient<'_> {
+    /// Get a file's metadata, limited to `fields`
+    pub async fn get_file(&self, file_id: &str, fields: &str) -> Result<DriveFile, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let response = self
+            .docs
+            .http()
+            .get(format!("{}/files/{}", GOOGLE_DRIVE_API_URL, file_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("fields", fields), ("supportsAllDrives", "true")])
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Find Google Docs matching `search`, one page at a time
+    pub async fn search_documents(&self, search: &DriveSearch) -> Result<DriveFileList, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let mut params = vec![
+            ("q", search.query()),
+            ("fields", SEARCH_FIELDS.to_string()),
+            ("pageSize", search.page_size.to_string()),
+            ("supportsAllDrives", "true".to_string()),
+            ("includeItemsFromAllDrives", "true".to_string()),
+        ];
+        // Drive does not support ordering full-text results, which are ranked by relevance
+        if search.full_text.is_none() {
+            params.push(("orderBy", "modifiedTime desc".to_string()));
+        }
+        if let Some(ref drive_id) = search.shared_drive_id {
+            params.push(("corpora", "drive".to_string()));
+            params.push(("driveId", drive_id.clone()));
+        }
+        if let Some(ref page_token) = search.page_token {
+            params.push(("pageToken", page_token.clone()));
+        }
+
+        let response = self
+            .docs
+            .http()
+            .get(format!("{}/files", GOOGLE_DRIVE_API_URL))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&params)
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Export a Google Docs file to another format
+    pub async fn export(&self, file_id: &str, format: ExportFormat) -> Result<Vec<u8>, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let response = self
+            .docs
+            .http()
+            .get(format!("{}/files/{}/export", GOOGLE_DRIVE_API_URL, file_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("mimeType", format.mime_type())])
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        let response = check_status(response).await?;
+        let bytes = response.bytes().await.map_err(handle_api_error)?;
+        Ok(bytes.to_vec())
+    }
+
+    /// List the comments on a file, oldest first, excluding deleted ones.
+    ///
+    /// When the page cap is reached the newest comments are the ones missing
+    /// and the listing is marked truncated.
+    pub async fn list_comments(&self, file_id: &str) -> Result<Listing<Comment>, McpError> {
+        let fields = format!("nextPageToken,comments({})", COMMENT_FIELDS);
+        let mut comments = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        for _ in 0..MAX_COMMENT_PAGES {
+            let token = self.docs.get_access_token().await?;
+            let mut params = vec![("fields", fields.as_str()), ("pageSize", "100")];
+            if let Some(ref page_token) = page_token {
+                params.push(("pageToken", page_token));
+            }
+
+            let response = self
+                .docs
+                .http()
+                .get(format!(
+                    "{}/files/{}/comments",
+                    GOOGLE_DRIVE_API_URL, file_id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .query(&params)
+                .send()
+                .await
+                .map_err(handle_api_error)?;
+            let page: CommentList = handle_response(response).await?;
+
+            comments.extend(page.comments);
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(Listing {
+            items: comments,
+            truncated: page_token.is_some(),
+        })
+    }
+
+    /// Add a comment to a file, optionally quoting the text it refers to
+    pub async fn create_comment(
+        &self,
+        file_id: &str,
+        content: &str,
+        quoted_text: Option<&str>,
+    ) -> Result<Comment, McpError> {
+        let mut body = serde_json::json!({ "content": content });
+        if let Some(quoted_text) = quoted_text {
+            body["quotedFileContent"] =
+                serde_json::json!({ "mimeType": "text/plain", "value": quoted_text });
+        }
+
+        let token = self.docs.get_access_token().await?;
+        let response = self
+            .docs
+            .http()
+            .post(format!(
+                "{}/files/{}/comments",
+                GOOGLE_DRIVE_API_URL, file_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("fields", COMMENT_FIELDS)])
+            .json(&body)
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Reply to a comment; `action` "resolve" or "reopen" also changes its state
+    pub async fn create_reply(
+        &self,
+        file_id: &str,
+        comment_id: &str,
+        content: Option<&str>,
+        action: Option<&str>,
+    ) -> Result<CommentReply, McpError> {
+        let mut body = serde_json::json!({});
+        if let Some(content) = content {
+            body["content"] = serde_json::json!(content);
+        }
+        if let Some(action) = action {
+            body["action"] = serde_json::json!(action);
+        }
+
+        let token = self.docs.get_access_token().await?;
+        let response = self
+            .docs
+            .http()
+            .post(format!(
+                "{}/files/{}/comments/{}/replies",
+                GOOGLE_DRIVE_API_URL, file_id, comment_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("fields", REPLY_FIELDS)])
+            .json(&body)
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// List a file's revisions, oldest first.
+    ///
+    /// Drive cannot list newest first, so when the page cap is reached the
+    /// newest revisions are the ones missing and the listing is marked truncated.
+    pub async fn list_revisions(&self, file_id: &str) -> Result<Listing<Revision>, McpError> {
+        let fields = format!("nextPageToken,revisions({})", REVISION_FIELDS);
+        let mut revisions = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        for _ in 0..MAX_REVISION_PAGES {
+            let token = self.docs.get_access_token().await?;
+            let mut params = vec![("fields", fields.as_str()), ("pageSize", "1000")];
+            if let Some(ref page_token) = page_token {
+                params.push(("pageToken", page_token));
+            }
+
+            let response = self
+                .docs
+                .http()
+                .get(format!(
+                    "{}/files/{}/revisions",
+                    GOOGLE_DRIVE_API_URL, file_id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .query(&params)
+                .send()
+                .await
+                .map_err(handle_api_error)?;
+            let page: RevisionList = handle_response(response).await?;
+
+            revisions.extend(page.revisions);
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(Listing {
+            items: revisions,
+            truncated: page_token.is_some(),
+        })
+    }
+
+    /// Get one revision's metadata
+    pub async fn get_revision(
+        &self,
+        file_id: &str,
+        revision_id: &str,
+    ) -> Result<Revision, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let response = self
+            .docs
+            .http()
+            .get(format!(
+                "{}/files/{}/revisions/{}",
+                GOOGLE_DRIVE_API_URL, file_id, revision_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("fields", REVISION_FIELDS)])
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Download the plain text of a revision.
+    ///
+    /// Line endings are normalized to `\n` and the byte order mark is removed.
+    pub async fn revision_text(&self, revision: &Revision) -> Result<String, McpError> {
+        let link = revision.export_links.get("text/plain").ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Revision {} cannot be exported as plain text; Google keeps the content of \
+                     older revisions only for some of them",
+                    revision.id
+                ),
+                None,
+            )
+        })?;
+
+        let token = self.docs.get_access_token().await?;
+        let response = self
+            .docs
+            .http()
+            .get(link)
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        let response = check_status(response).await?;
+        let text = response.text().await.map_err(handle_api_error)?;
+        Ok(text.trim_start_matches('\u{feff}').replace("\r\n", "\n"))
+    }
+
+    /// List who has access to a file, marking the listing truncated when the
+    /// page cap is reached
+    pub async fn list_permissions(&self, file_id: &str) -> Result<Listing<Permission>, McpError> {
+        let fields = format!("nextPageToken,permissions({})", PERMISSION_FIELDS);
+        let mut permissions = Vec::new();
+        let mut page_token: Option<String> = None;
+
+        for _ in 0..MAX_PERMISSION_PAGES {
+            let token = self.docs.get_access_token().await?;
+            let mut params = vec![
+                ("fields", fields.as_str()),
+                ("pageSize", "100"),
+                ("supportsAllDrives", "true"),
+            ];
+            if let Some(ref page_token) = page_token {
+                params.push(("pageToken", page_token));
+            }
+
+            let response = self
+                .docs
+                .http()
+                .get(format!(
+                    "{}/files/{}/permissions",
+                    GOOGLE_DRIVE_API_URL, file_id
+                ))
+                .header("Authorization", format!("Bearer {}", token))
+                .query(&params)
+                .send()
+                .await
+                .map_err(handle_api_error)?;
+            let page: PermissionList = handle_response(response).await?;
+
+            permissions.extend(page.permissions);
+            page_token = page.next_page_token;
+            if page_token.is_none() {
+                break;
+            }
+        }
+        Ok(Listing {
+            items: permissions,
+            truncated: page_token.is_some(),
+        })
+    }
+
+    /// Grant access to a file.
+    ///
+    /// `permission` is a Drive permission resource such as
+    /// `{"type": "user", "role": "reader", "emailAddress": "..."}`.
+    pub async fn create_permission(
+        &self,
+        file_id: &str,
+        permission: &serde_json::Value,
+        notify: bool,
+        message: Option<&str>,
+    ) -> Result<Permission, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let notify = notify.to_string();
+        let mut params = vec![
+            ("fields", PERMISSION_FIELDS),
+            ("supportsAllDrives", "true"),
+            ("sendNotificationEmail", notify.as_str()),
+        ];
+        if let Some(message) = message {
+            params.push(("emailMessage", message));
+        }
+
+        let response = self
+            .docs
+            .http()
+            .post(format!(
+                "{}/files/{}/permissions",
+                GOOGLE_DRIVE_API_URL, file_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&params)
+            .json(permission)
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Revoke a permission on a file
+    pub async fn delete_permission(
+        &self,
+        file_id: &str,
+        permission_id: &str,
+    ) -> Result<(), McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let response = self
+            .docs
+            .http()
+            .delete(format!(
+                "{}/files/{}/permissions/{}",
+                GOOGLE_DRIVE_API_URL, file_id, permission_id
+            ))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[("supportsAllDrives", "true")])
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        check_status(response).await.map(|_| ())
+    }
+
+    /// Copy a file, optionally into a folder (otherwise next to the original)
+    pub async fn copy_file(
+        &self,
+        file_id: &str,
+        name: &str,
+        folder_id: Option<&str>,
+    ) -> Result<DriveFile, McpError> {
+        let token = self.docs.get_access_token().await?;
+
+        let mut body = serde_json::json!({ "name": name });
+        if let Some(folder_id) = folder_id {
+            body["parents"] = serde_json::json!([folder_id]);
+        }
+
+        let response = self
+            .docs
+            .http()
+            .post(format!("{}/files/{}/copy", GOOGLE_DRIVE_API_URL, file_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[
+                ("fields", "id,name,parents,webViewLink"),
+                ("supportsAllDrives", "true"),
+            ])
+            .json(&body)
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+
+    /// Move a file into a folder, removing it from its current folders
+    pub async fn move_to_folder(
+        &self,
+        file_id: &str,
+        folder_id: &str,
+    ) -> Result<DriveFile, McpError> {
+        let current = self.get_file(file_id, "id,parents").await?;
+        let remove_parents = current.parents.join(",");
+
+        let token = self.docs.get_access_token().await?;
+        let response = self
+            .docs
+            .http()
+            .patch(format!("{}/files/{}", GOOGLE_DRIVE_API_URL, file_id))
+            .header("Authorization", format!("Bearer {}", token))
+            .query(&[
+                ("addParents", folder_id),
+                ("removeParents", remove_parents.as_str()),
+                ("fields", "id,name,parents"),
+                ("supportsAllDrives", "true"),
+            ])
+            .json(&serde_json::json!({}))
+            .send()
+            .await
+            .map_err(handle_api_error)?;
+
+        handle_response(response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn empty_search_matches_all_documents() {
+        // Given: No search criteria
+        let search = DriveSearch::default();
+
+        // When: Building the query
+        let query = search.query();
+
+        // Then: Only untrashed Google Docs should match
+        assert_eq!(
+            query,
+            "mimeType = 'application/vnd.google-apps.document' and trashed = false"
+        );
+    }
+
+    #[test]
+    fn search_criteria_are_combined_and_escaped() {
+        // Given: Criteria whose values contain quotes and backslashes
+        let search = DriveSearch {
+            name: Some("Bob's RFC".to_string()),
+            full_text: Some(r"C:\temp".to_string()),
+            owner: Some("alice@example.com".to_string()),
+            folder_id: Some("folder-1".to_string()),
+            modified_after: Some("2024-01-01T00:00:00Z".to_string()),
+            ..Default::default()
+        };
+
+        // When: Building the query
+        let query = search.query();
+
+        // Then: Every clause should be present with literals escaped
+        assert!(query.contains(r"name contains 'Bob\'s RFC'"));
+        assert!(query.contains(r"fullText contains 'C:\\temp'"));
+        assert!(query.contains("'alice@example.com' in owners"));
+        assert!(query.contains("'folder-1' in parents"));
+        assert!(query.contains("modifiedTime >= '2024-01-01T00:00:00Z'"));
+    }
+}
@@ -1,3 +1,9 @@
 mod client;
+mod credentials;
+mod drive;
+mod profiles;
 
 pub use client::*;
+pub use credentials::*;
+pub use drive::*;
+pub use profiles::*;
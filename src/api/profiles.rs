@@ -0,0 +1,339 @@
+use super::GoogleDocsClient;
+use crate::config::{ConfigFile, IdentityConfig, WILDCARD_IDENTITY};
+use crate::models::Credentials;
+use rmcp::ErrorData as McpError;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Impersonating clients keyed by (profile, subject)
+type ImpersonatedClients = HashMap<(String, String), Arc<GoogleDocsClient>>;
+
+/// Named API clients, each with its own credentials and token cache
+#[derive(Clone)]
+pub struct ClientProfiles {
+    clients: BTreeMap<String, Arc<GoogleDocsClient>>,
+    default_profile: String,
+    identities: BTreeMap<String, IdentityConfig>,
+    impersonated: Arc<Mutex<ImpersonatedClients>>,
+}
+
+impl ClientProfiles {
+    /// Profile name used when the server is configured with a single client
+    pub const DEFAULT_NAME: &'static str = "default";
+
+    /// Wrap a single client as the only, default profile
+    pub fn single(client: GoogleDocsClient) -> Self {
+        Self {
+            clients: BTreeMap::from([(Self::DEFAULT_NAME.to_string(), Arc::new(client))]),
+            default_profile: Self::DEFAULT_NAME.to_string(),
+            identities: BTreeMap::new(),
+            impersonated: Arc::default(),
+        }
+    }
+
+    /// Create profiles from named clients and the name of the default one
+    pub fn new(
+        clients: BTreeMap<String, GoogleDocsClient>,
+        default_profile: impl Into<String>,
+    ) -> Result<Self, McpError> {
+        let default_profile = default_profile.into();
+        if !clients.contains_key(&default_profile) {
+            return Err(McpError::invalid_params(
+                format!("Default profile \"{}\" is not configured", default_profile),
+                None,
+            ));
+        }
+
+        Ok(Self {
+            clients: clients
+                .into_iter()
+                .map(|(name, client)| (name, Arc::new(client)))
+                .collect(),
+            default_profile,
+            identities: BTreeMap::new(),
+            impersonated: Arc::default(),
+        })
+    }
+
+    /// Map authenticated client identities to profiles and impersonated users.
+    ///
+    /// Once any mapping is set, identities without an entry (and no `*`
+    /// entry) are refused.
+    pub fn with_identities(
+        mut self,
+        identities: BTreeMap<String, IdentityConfig>,
+    ) -> Result<Self, McpError> {
+        for (identity, mapping) in &identities {
+            let profile = mapping.profile.as_deref().unwrap_or(&self.default_profile);
+            let client = self.get(Some(profile)).map_err(|e| {
+                McpError::invalid_params(format!("Identity \"{}\": {}", identity, e.message), None)
+            })?;
+            if mapping.subject.is_some()
+                && !matches!(client.credentials(), Credentials::ServiceAccount(_))
+            {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Identity \"{}\": impersonation requires profile \"{}\" to use service account credentials",
+                        identity, profile
+                    ),
+                    None,
+                ));
+            }
+        }
+        self.identities = identities;
+        Ok(self)
+    }
+
+    /// Load every profile's credentials from a configuration file
+    pub fn from_config(config: &ConfigFile) -> Result<Self, McpError> {
+        let mut clients = BTreeMap::new();
+        for (name, profile) in &config.profiles {
+            let source = profile.credential_source(name)?;
+            let mut client = GoogleDocsClient::from_source(&source).map_err(|e| {
+                McpError::invalid_params(format!("Profile \"{}\": {}", name, e.message), None)
+            })?;
+            if let Some(ref subject) = profile.subject {
+                client = client.with_subject(subject).map_err(|e| {
+                    McpError::invalid_params(format!("Profile \"{}\": {}", name, e.message), None)
+                })?;
+            }
+            clients.insert(name.clone(), client);
+        }
+
+        Self::new(clients, config.default_profile_name()?)?
+            .with_identities(config.identities.clone())
+    }
+
+    /// Pick the client for a tool call made by an authenticated client.
+    ///
+    /// Without an identity (stdio, or a single shared bearer token) or without
+    /// identity mappings this is the same as [`ClientProfiles::get`]. Otherwise
+    /// the identity's mapping decides the profile and the Workspace user to
+    /// impersonate, so edits are attributed to that user.
+    pub fn resolve(
+        &self,
+        identity: Option<&str>,
+        profile: Option<&str>,
+    ) -> Result<Arc<GoogleDocsClient>, McpError> {
+        let Some(identity) = identity else {
+            return self.get(profile);
+        };
+        if self.identities.is_empty() {
+            return self.get(profile);
+        }
+
+        let mapping = self
+            .identities
+            .get(identity)
+            .or_else(|| self.identities.get(WILDCARD_IDENTITY))
+            .ok_or_else(|| {
+                McpError::invalid_params(
+                    format!(
+                        "Client identity \"{}\" is not allowed to use this server",
+                        identity
+                    ),
+                    None,
+                )
+            })?;
+
+        let profile = match (profile, mapping.profile.as_deref()) {
+            (Some(requested), Some(mapped)) if requested != mapped => {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Client identity \"{}\" may only use profile \"{}\"",
+                        identity, mapped
+                    ),
+                    None,
+                ));
+            }
+            (requested, mapped) => mapped.or(requested).unwrap_or(&self.default_profile),
+        };
+
+        let client = self.get(Some(profile))?;
+        match mapping.subject_for(identity) {
+            Some(subject) => self.impersonate(profile, &client, subject),
+            None => Ok(client),
+        }
+    }
+
+    /// Get or create the client impersonating `subject` with a profile's credentials
+    fn impersonate(
+        &self,
+        profile: &str,
+        client: &GoogleDocsClient,
+        subject: String,
+    ) -> Result<Arc<GoogleDocsClient>, McpError> {
+        let key = (profile.to_string(), subject);
+        let mut impersonated = self
+            .impersonated
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some(client) = impersonated.get(&key) {
+            return Ok(client.clone());
+        }
+
+        tracing::info!("profile {} impersonating {}", key.0, key.1);
+        let client = Arc::new(client.clone().with_subject(key.1.clone())?);
+        impersonated.insert(key, client.clone());
+        Ok(client)
+    }
+
+    /// Look up a profile's client, falling back to the default profile
+    pub fn get(&self, profile: Option<&str>) -> Result<Arc<GoogleDocsClient>, McpError> {
+        let name = profile.unwrap_or(&self.default_profile);
+        self.clients.get(name).cloned().ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Unknown profile \"{}\". Available profiles: {}",
+                    name,
+                    self.names().collect::<Vec<_>>().join(", ")
+                ),
+                None,
+            )
+        })
+    }
+
+    /// Names of all configured profiles
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.clients.keys().map(String::as_str)
+    }
+
+    /// Name of the profile used when a tool call does not name one
+    pub fn default_profile(&self) -> &str {
+        &self.default_profile
+    }
+
+    /// Number of configured profiles
+    pub fn len(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Whether no profiles are configured
+    pub fn is_empty(&self) -> bool {
+        self.clients.is_empty()
+    }
+
+    /// Apply the same scopes to every profile's client
+    pub(crate) fn with_scopes(self, scopes: Vec<String>) -> Self {
+        Self {
+            clients: self
+                .clients
+                .into_iter()
+                .map(|(name, client)| {
+                    let client = Arc::unwrap_or_clone(client).with_scopes(scopes.clone());
+                    (name, Arc::new(client))
+                })
+                .collect(),
+            default_profile: self.default_profile,
+            identities: self.identities,
+            impersonated: Arc::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY_JSON: &str = include_str!("../../tests/fixtures/service-account.json");
+
+    fn profiles(identities: &[(&str, IdentityConfig)]) -> ClientProfiles {
+        let client = GoogleDocsClient::from_json_str(KEY_JSON).unwrap();
+        let clients = BTreeMap::from([
+            ("acme".to_string(), client.clone()),
+            ("globex".to_string(), client),
+        ]);
+        ClientProfiles::new(clients, "acme")
+            .unwrap()
+            .with_identities(
+                identities
+                    .iter()
+                    .map(|(identity, mapping)| (identity.to_string(), mapping.clone()))
+                    .collect(),
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn requests_without_identity_use_requested_profile() {
+        // Given: Profiles with an identity mapping
+        let profiles = profiles(&[("alice", IdentityConfig::default())]);
+
+        // When: Resolving a call made without an authenticated identity
+        let client = profiles.resolve(None, Some("globex")).unwrap();
+
+        // Then: No impersonation should happen
+        assert_eq!(client.subject(), None);
+    }
+
+    #[test]
+    fn mapped_identity_impersonates_its_subject() {
+        // Given: alice is mapped to impersonate her Workspace account
+        let profiles = profiles(&[(
+            "alice",
+            IdentityConfig {
+                profile: Some("globex".to_string()),
+                subject: Some("alice@globex.example".to_string()),
+            },
+        )]);
+
+        // When: Resolving two calls made by alice
+        let first = profiles.resolve(Some("alice"), None).unwrap();
+        let second = profiles.resolve(Some("alice"), None).unwrap();
+
+        // Then: Both should share one impersonating client
+        assert_eq!(first.subject(), Some("alice@globex.example"));
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn wildcard_identity_substitutes_the_identity() {
+        // Given: Every identity impersonates itself
+        let profiles = profiles(&[(
+            WILDCARD_IDENTITY,
+            IdentityConfig {
+                profile: None,
+                subject: Some("{identity}".to_string()),
+            },
+        )]);
+
+        // When: Resolving a call made by a JWT subject
+        let client = profiles.resolve(Some("bob@acme.example"), None).unwrap();
+
+        // Then: The subject should be the identity itself
+        assert_eq!(client.subject(), Some("bob@acme.example"));
+    }
+
+    #[test]
+    fn unmapped_identity_is_refused() {
+        // Given: Only alice is mapped
+        let profiles = profiles(&[("alice", IdentityConfig::default())]);
+
+        // When: Resolving a call made by someone else
+        let err = profiles.resolve(Some("mallory"), None).map(|_| ()).unwrap_err();
+
+        // Then: The call should be refused
+        assert!(err.message.contains("not allowed"));
+    }
+
+    #[test]
+    fn mapped_profile_cannot_be_overridden() {
+        // Given: alice is restricted to the acme profile
+        let profiles = profiles(&[(
+            "alice",
+            IdentityConfig {
+                profile: Some("acme".to_string()),
+                subject: None,
+            },
+        )]);
+
+        // When: alice asks for another profile
+        let err = profiles
+            .resolve(Some("alice"), Some("globex"))
+            .map(|_| ())
+            .unwrap_err();
+
+        // Then: The call should be refused
+        assert!(err.message.contains("may only use profile \"acme\""));
+    }
+}
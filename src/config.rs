@@ -0,0 +1,668 @@
+use crate::api::CredentialSource;
+use crate::constants::{
+    GOOGLE_DOCS_READONLY_SCOPE, GOOGLE_DOCS_SCOPE, GOOGLE_DRIVE_METADATA_READONLY_SCOPE,
+    GOOGLE_DRIVE_READONLY_SCOPE, GOOGLE_DRIVE_SCOPE,
+};
+use rmcp::ErrorData as McpError;
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Environment variable enabling read-only mode
+pub const READ_ONLY_ENV: &str = "GOOGLE_DOCS_READ_ONLY";
+
+/// Environment variable holding the path to the JSON configuration file
+pub const CONFIG_PATH_ENV: &str = "GOOGLE_DOCS_MCP_CONFIG";
+
+/// Environment variable holding a comma-separated list of Drive features to enable
+pub const DRIVE_FEATURES_ENV: &str = "GOOGLE_DOCS_DRIVE_FEATURES";
+
+/// Environment variable holding the directory exported files are written to
+pub const EXPORT_DIR_ENV: &str = "GOOGLE_DOCS_EXPORT_DIR";
+
+/// Environment variable holding a comma-separated list of domains documents may be shared with
+pub const SHARE_DOMAINS_ENV: &str = "GOOGLE_DOCS_SHARE_DOMAINS";
+
+/// Entry in [`SHARE_DOMAINS_ENV`] allowing any domain
+pub const ANY_DOMAIN: &str = "*";
+
+/// Environment variable holding how often subscribed documents are checked for changes, in seconds
+pub const POLL_INTERVAL_ENV: &str = "GOOGLE_DOCS_POLL_INTERVAL_SECS";
+
+/// How often subscribed documents are checked for changes by default
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Shortest accepted polling interval, to stay well within Docs API quotas
+const MIN_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Environment variable holding how many characters a single deletion may
+/// span before the user is asked to confirm it
+pub const CONFIRM_DELETE_CHARS_ENV: &str = "GOOGLE_DOCS_CONFIRM_DELETE_CHARS";
+
+/// Characters a single deletion may span without confirmation by default
+pub const DEFAULT_CONFIRM_DELETE_CHARS: usize = 500;
+
+/// Optional features backed by the Google Drive API.
+///
+/// Each one widens the OAuth scopes the server requests, so they are off by
+/// default and must be enabled explicitly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DriveFeature {
+    /// File newly created documents into Drive folders
+    Folders,
+    /// Search and list documents
+    Search,
+    /// Create documents by copying templates
+    Templates,
+    /// Export documents to PDF, DOCX and other formats
+    Export,
+    /// Read comments, and add, reply to and resolve them unless read-only
+    Comments,
+    /// List revisions and read their content
+    Revisions,
+    /// List who has access to documents, and share and revoke access unless read-only
+    Sharing,
+}
+
+impl DriveFeature {
+    /// Every feature, in the order they are documented
+    pub const ALL: &'static [DriveFeature] = &[
+        DriveFeature::Folders,
+        DriveFeature::Search,
+        DriveFeature::Templates,
+        DriveFeature::Export,
+        DriveFeature::Comments,
+        DriveFeature::Revisions,
+        DriveFeature::Sharing,
+    ];
+
+    /// The name used in [`DRIVE_FEATURES_ENV`]
+    pub fn name(self) -> &'static str {
+        match self {
+            DriveFeature::Folders => "folders",
+            DriveFeature::Search => "search",
+            DriveFeature::Templates => "templates",
+            DriveFeature::Export => "export",
+            DriveFeature::Comments => "comments",
+            DriveFeature::Revisions => "revisions",
+            DriveFeature::Sharing => "sharing",
+        }
+    }
+
+    /// The extra OAuth scope this feature needs, if it is usable in this mode
+    fn scope(self, read_only: bool) -> Option<&'static str> {
+        match self {
+            DriveFeature::Folders | DriveFeature::Templates if read_only => None,
+            DriveFeature::Folders | DriveFeature::Templates => Some(GOOGLE_DRIVE_SCOPE),
+            DriveFeature::Search => Some(GOOGLE_DRIVE_METADATA_READONLY_SCOPE),
+            DriveFeature::Sharing if read_only => Some(GOOGLE_DRIVE_METADATA_READONLY_SCOPE),
+            DriveFeature::Sharing => Some(GOOGLE_DRIVE_SCOPE),
+            DriveFeature::Export | DriveFeature::Revisions => Some(GOOGLE_DRIVE_READONLY_SCOPE),
+            DriveFeature::Comments if read_only => Some(GOOGLE_DRIVE_READONLY_SCOPE),
+            DriveFeature::Comments => Some(GOOGLE_DRIVE_SCOPE),
+        }
+    }
+}
+
+impl FromStr for DriveFeature {
+    type Err = McpError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        DriveFeature::ALL
+            .iter()
+            .copied()
+            .find(|feature| feature.name().eq_ignore_ascii_case(s.trim()))
+            .ok_or_else(|| {
+                McpError::invalid_params(
+                    format!(
+                        "Unknown Drive feature {:?}; expected one of: {}",
+                        s,
+                        DriveFeature::ALL
+                            .iter()
+                            .map(|feature| feature.name())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                    None,
+                )
+            })
+    }
+}
+
+/// Runtime options controlling what the server is allowed to do
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    /// Request only read-only scopes and do not register tools that modify documents
+    pub read_only: bool,
+
+    /// Drive API features to enable
+    pub drive_features: BTreeSet<DriveFeature>,
+
+    /// Directory exported files are written to; without one, exports are
+    /// only returned inline
+    pub export_dir: Option<PathBuf>,
+
+    /// Domains documents may be shared with (lowercase); `*` allows any.
+    /// Sharing is refused while this is empty.
+    pub share_domains: Vec<String>,
+
+    /// How often documents with resource subscriptions are checked for changes
+    pub poll_interval: Duration,
+
+    /// Deletions spanning more characters than this need the user's confirmation
+    pub confirm_delete_chars: usize,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            read_only: false,
+            drive_features: BTreeSet::new(),
+            export_dir: None,
+            share_domains: Vec::new(),
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            confirm_delete_chars: DEFAULT_CONFIRM_DELETE_CHARS,
+        }
+    }
+}
+
+impl ServerOptions {
+    /// Read options from environment variables
+    pub fn from_env() -> Result<Self, McpError> {
+        let drive_features = match std::env::var(DRIVE_FEATURES_ENV) {
+            Ok(value) => parse_drive_features(&value)?,
+            Err(_) => BTreeSet::new(),
+        };
+        let export_dir = std::env::var_os(EXPORT_DIR_ENV)
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from);
+        let share_domains = std::env::var(SHARE_DOMAINS_ENV)
+            .map(|value| {
+                value
+                    .split(',')
+                    .map(|domain| domain.trim().trim_start_matches('@').to_ascii_lowercase())
+                    .filter(|domain| !domain.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        Ok(Self {
+            read_only: env_flag(READ_ONLY_ENV)?.unwrap_or(false),
+            drive_features,
+            export_dir,
+            share_domains,
+            poll_interval: poll_interval()?.unwrap_or(DEFAULT_POLL_INTERVAL),
+            confirm_delete_chars: confirm_delete_chars()?.unwrap_or(DEFAULT_CONFIRM_DELETE_CHARS),
+        })
+    }
+
+    /// Whether documents may be shared with `domain` or a user in it.
+    ///
+    /// Subdomains of an allowed domain are allowed as well.
+    pub fn share_domain_allowed(&self, domain: &str) -> bool {
+        let domain = domain.trim().to_ascii_lowercase();
+        self.share_domains.iter().any(|allowed| {
+            allowed == ANY_DOMAIN
+                || domain == *allowed
+                || domain
+                    .strip_suffix(allowed.as_str())
+                    .is_some_and(|prefix| prefix.ends_with('.'))
+        })
+    }
+
+    /// Whether a Drive feature is enabled
+    pub fn drive_enabled(&self, feature: DriveFeature) -> bool {
+        self.drive_features.contains(&feature)
+    }
+
+    /// The OAuth scopes access tokens should be requested with
+    pub fn scopes(&self) -> Vec<String> {
+        let docs_scope = if self.read_only {
+            GOOGLE_DOCS_READONLY_SCOPE
+        } else {
+            GOOGLE_DOCS_SCOPE
+        };
+        let mut scopes = vec![docs_scope.to_string()];
+        for feature in &self.drive_features {
+            if let Some(scope) = feature.scope(self.read_only)
+                && !scopes.iter().any(|s| s == scope)
+            {
+                scopes.push(scope.to_string());
+            }
+        }
+        scopes
+    }
+}
+
+/// Parse a comma-separated list of Drive feature names
+fn parse_drive_features(value: &str) -> Result<BTreeSet<DriveFeature>, McpError> {
+    value
+        .split(',')
+        .filter(|name| !name.trim().is_empty())
+        .map(str::parse)
+        .collect()
+}
+
+/// Contents of the JSON configuration file
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Profile used when a tool call does not name one (optional with a single profile)
+    #[serde(default)]
+    pub default_profile: Option<String>,
+
+    /// Named credential profiles
+    pub profiles: BTreeMap<String, ProfileConfig>,
+
+    /// Bearer tokens accepted by the HTTP transport, keyed by the client identity they authenticate
+    #[serde(default)]
+    pub http_tokens: BTreeMap<String, TokenConfig>,
+
+    /// Profile and impersonated user per authenticated HTTP client identity.
+    /// The `*` entry applies to identities without their own entry.
+    #[serde(default)]
+    pub identities: BTreeMap<String, IdentityConfig>,
+}
+
+/// A named set of credentials, e.g. one per Workspace tenant
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Path to a service account key or authorized user credentials file,
+    /// relative to the configuration file
+    #[serde(default)]
+    pub credentials_file: Option<PathBuf>,
+
+    /// Environment variable holding the credentials JSON (plain or base64)
+    #[serde(default)]
+    pub credentials_env: Option<String>,
+
+    /// Workspace user to impersonate through domain-wide delegation
+    #[serde(default)]
+    pub subject: Option<String>,
+}
+
+/// A bearer token for one HTTP client
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenConfig {
+    /// Environment variable holding the token
+    pub token_env: String,
+}
+
+/// What an authenticated HTTP client acts as
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct IdentityConfig {
+    /// Credential profile the client must use (any profile when unset)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Workspace user to impersonate; `{identity}` is replaced by the client identity
+    #[serde(default)]
+    pub subject: Option<String>,
+}
+
+/// Placeholder in [`IdentityConfig::subject`] replaced by the client identity
+pub const IDENTITY_PLACEHOLDER: &str = "{identity}";
+
+/// Key in [`ConfigFile::identities`] matching identities without their own entry
+pub const WILDCARD_IDENTITY: &str = "*";
+
+impl IdentityConfig {
+    /// The Workspace user to impersonate for `identity`, if any
+    pub fn subject_for(&self, identity: &str) -> Option<String> {
+        self.subject
+            .as_ref()
+            .map(|subject| subject.replace(IDENTITY_PLACEHOLDER, identity))
+    }
+}
+
+impl ConfigFile {
+    /// Read the configuration file at `path`.
+    ///
+    /// Relative `credentials_file` paths are resolved against the directory
+    /// containing the configuration file.
+    pub fn load(path: &Path) -> Result<Self, McpError> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            McpError::invalid_params(
+                format!(
+                    "Failed to read configuration file {}: {}",
+                    path.display(),
+                    e
+                ),
+                None,
+            )
+        })?;
+        let mut config: Self = serde_json::from_str(&content).map_err(|e| {
+            McpError::invalid_params(
+                format!(
+                    "Failed to parse configuration file {}: {}",
+                    path.display(),
+                    e
+                ),
+                None,
+            )
+        })?;
+
+        if let Some(dir) = path.parent() {
+            for profile in config.profiles.values_mut() {
+                if let Some(ref mut file) = profile.credentials_file
+                    && file.is_relative()
+                {
+                    *file = dir.join(&*file);
+                }
+            }
+        }
+
+        config.default_profile_name()?;
+        for (identity, mapping) in &config.identities {
+            if let Some(ref profile) = mapping.profile
+                && !config.profiles.contains_key(profile)
+            {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Identity \"{}\" maps to unknown profile \"{}\"",
+                        identity, profile
+                    ),
+                    None,
+                ));
+            }
+        }
+        Ok(config)
+    }
+
+    /// The profile to use when a tool call does not name one
+    pub fn default_profile_name(&self) -> Result<&str, McpError> {
+        if let Some(ref name) = self.default_profile {
+            if !self.profiles.contains_key(name) {
+                return Err(McpError::invalid_params(
+                    format!("default_profile \"{}\" is not a configured profile", name),
+                    None,
+                ));
+            }
+            return Ok(name);
+        }
+
+        let mut names = self.profiles.keys();
+        match (names.next(), names.next()) {
+            (Some(only), None) => Ok(only),
+            (None, _) => Err(McpError::invalid_params(
+                "Configuration file defines no profiles".to_string(),
+                None,
+            )),
+            (Some(_), Some(_)) => Err(McpError::invalid_params(
+                "default_profile is required when more than one profile is configured".to_string(),
+                None,
+            )),
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Where this profile's credentials are loaded from
+    pub fn credential_source(&self, name: &str) -> Result<CredentialSource, McpError> {
+        match (&self.credentials_file, &self.credentials_env) {
+            (Some(file), None) => Ok(CredentialSource::File(file.clone())),
+            (None, Some(var)) => {
+                let value = std::env::var(var).map_err(|_| {
+                    McpError::invalid_params(
+                        format!(
+                            "Profile \"{}\": environment variable {} is not set",
+                            name, var
+                        ),
+                        None,
+                    )
+                })?;
+                Ok(CredentialSource::Inline(value))
+            }
+            _ => Err(McpError::invalid_params(
+                format!(
+                    "Profile \"{}\" must set exactly one of credentials_file or credentials_env",
+                    name
+                ),
+                None,
+            )),
+        }
+    }
+}
+
+/// Parse a boolean environment variable, returning `None` when it is unset
+fn env_flag(name: &str) -> Result<Option<bool>, McpError> {
+    let Ok(value) = std::env::var(name) else {
+        return Ok(None);
+    };
+    match value.trim().to_ascii_lowercase().as_str() {
+        "" => Ok(None),
+        "1" | "true" | "yes" | "on" => Ok(Some(true)),
+        "0" | "false" | "no" | "off" => Ok(Some(false)),
+        _ => Err(McpError::invalid_params(
+            format!("{} must be true or false, got {:?}", name, value),
+            None,
+        )),
+    }
+}
+
+/// Parse [`POLL_INTERVAL_ENV`], if set
+fn poll_interval() -> Result<Option<Duration>, McpError> {
+    let Ok(value) = std::env::var(POLL_INTERVAL_ENV) else {
+        return Ok(None);
+    };
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    parse_poll_interval(&value).map(Some)
+}
+
+/// Parse a polling interval in whole seconds
+fn parse_poll_interval(value: &str) -> Result<Duration, McpError> {
+    let interval = value
+        .trim()
+        .parse()
+        .map(Duration::from_secs)
+        .map_err(|_| {
+            McpError::invalid_params(
+                format!("{} must be a number of seconds, got {:?}", POLL_INTERVAL_ENV, value),
+                None,
+            )
+        })?;
+    if interval < MIN_POLL_INTERVAL {
+        return Err(McpError::invalid_params(
+            format!(
+                "{} must be at least {} seconds",
+                POLL_INTERVAL_ENV,
+                MIN_POLL_INTERVAL.as_secs()
+            ),
+            None,
+        ));
+    }
+    Ok(interval)
+}
+
+/// Parse [`CONFIRM_DELETE_CHARS_ENV`], if set
+fn confirm_delete_chars() -> Result<Option<usize>, McpError> {
+    let Ok(value) = std::env::var(CONFIRM_DELETE_CHARS_ENV) else {
+        return Ok(None);
+    };
+    if value.trim().is_empty() {
+        return Ok(None);
+    }
+    value.trim().parse().map(Some).map_err(|_| {
+        McpError::invalid_params(
+            format!(
+                "{} must be a number of characters, got {:?}",
+                CONFIRM_DELETE_CHARS_ENV, value
+            ),
+            None,
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_options_request_full_documents_scope() {
+        // Given: Default options
+        let options = ServerOptions::default();
+
+        // When: Computing the scopes
+        let scopes = options.scopes();
+
+        // Then: Only the read-write documents scope should be requested
+        assert_eq!(scopes, vec![GOOGLE_DOCS_SCOPE.to_string()]);
+    }
+
+    #[test]
+    fn read_only_options_request_readonly_scope() {
+        // Given: Read-only options
+        let options = ServerOptions {
+            read_only: true,
+            ..Default::default()
+        };
+
+        // When: Computing the scopes
+        let scopes = options.scopes();
+
+        // Then: The read-write scope must not be requested
+        assert_eq!(scopes, vec![GOOGLE_DOCS_READONLY_SCOPE.to_string()]);
+    }
+
+    #[test]
+    fn folders_feature_adds_drive_scope_unless_read_only() {
+        // Given: The folders feature, with and without read-only mode
+        let features = parse_drive_features(" Folders, ").unwrap();
+        let writable = ServerOptions {
+            read_only: false,
+            drive_features: features.clone(),
+            ..Default::default()
+        };
+        let read_only = ServerOptions {
+            read_only: true,
+            drive_features: features,
+            ..Default::default()
+        };
+
+        // When: Computing the scopes
+        // Then: The Drive scope should only be requested when documents can be written
+        assert_eq!(
+            writable.scopes(),
+            vec![GOOGLE_DOCS_SCOPE.to_string(), GOOGLE_DRIVE_SCOPE.to_string()]
+        );
+        assert_eq!(
+            read_only.scopes(),
+            vec![GOOGLE_DOCS_READONLY_SCOPE.to_string()]
+        );
+    }
+
+    #[test]
+    fn unknown_drive_feature_is_rejected() {
+        // Given: A feature list with a typo
+        // When: Parsing it
+        let err = parse_drive_features("folders,foldrs").unwrap_err();
+
+        // Then: The valid names should be listed
+        assert!(err.message.contains("expected one of: folders, search, templates, export, comments, revisions, sharing"));
+    }
+
+    #[test]
+    fn search_feature_adds_metadata_scope_in_read_only_mode() {
+        // Given: Read-only mode with search enabled
+        let options = ServerOptions {
+            read_only: true,
+            drive_features: parse_drive_features("search").unwrap(),
+            ..Default::default()
+        };
+
+        // When: Computing the scopes
+        let scopes = options.scopes();
+
+        // Then: Only the read-only metadata scope should be added
+        assert_eq!(
+            scopes,
+            vec![
+                GOOGLE_DOCS_READONLY_SCOPE.to_string(),
+                GOOGLE_DRIVE_METADATA_READONLY_SCOPE.to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn share_domains_allow_exact_domains_and_subdomains() {
+        // Given: An allowlist with one domain
+        let options = ServerOptions {
+            share_domains: vec!["example.com".to_string()],
+            ..Default::default()
+        };
+
+        // When: Checking candidate domains
+        // Then: Only the domain and its subdomains should be allowed
+        assert!(options.share_domain_allowed("Example.com"));
+        assert!(options.share_domain_allowed("eng.example.com"));
+        assert!(!options.share_domain_allowed("badexample.com"));
+        assert!(!options.share_domain_allowed("example.com.evil.org"));
+        assert!(!ServerOptions::default().share_domain_allowed("example.com"));
+    }
+
+    #[test]
+    fn single_profile_is_the_default() {
+        // Given: A configuration with one profile and no default_profile
+        let config: ConfigFile =
+            serde_json::from_str(r#"{"profiles": {"acme": {"credentials_file": "acme.json"}}}"#)
+                .unwrap();
+
+        // When: Resolving the default profile
+        let name = config.default_profile_name().unwrap();
+
+        // Then: The only profile should be used
+        assert_eq!(name, "acme");
+    }
+
+    #[test]
+    fn several_profiles_require_a_default() {
+        // Given: Two profiles and no default_profile
+        let config: ConfigFile = serde_json::from_str(
+            r#"{"profiles": {
+                "acme": {"credentials_file": "acme.json"},
+                "globex": {"credentials_file": "globex.json", "subject": "bot@globex.com"}
+            }}"#,
+        )
+        .unwrap();
+
+        // When: Resolving the default profile
+        let err = config.default_profile_name().unwrap_err();
+
+        // Then: The configuration should be rejected
+        assert!(err.message.contains("default_profile is required"));
+    }
+
+    #[test]
+    fn profile_requires_exactly_one_credential_source() {
+        // Given: A profile with both a file and an environment variable
+        let profile: ProfileConfig = serde_json::from_str(
+            r#"{"credentials_file": "acme.json", "credentials_env": "ACME_KEY"}"#,
+        )
+        .unwrap();
+
+        // When: Resolving its credential source
+        let err = profile.credential_source("acme").unwrap_err();
+
+        // Then: The ambiguity should be reported
+        assert!(
+            err.message
+                .contains("exactly one of credentials_file or credentials_env")
+        );
+    }
+
+    #[test]
+    fn poll_interval_must_not_be_too_short() {
+        // Given: Polling intervals in seconds
+        // When: Parsing them
+        // Then: Intervals under the minimum should be rejected
+        assert_eq!(parse_poll_interval(" 60 ").unwrap(), Duration::from_secs(60));
+        assert!(parse_poll_interval("1").is_err());
+        assert!(parse_poll_interval("soon").is_err());
+    }
+}
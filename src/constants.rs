@@ -7,5 +7,24 @@ pub const GOOGLE_TOKEN_URL: &str = "https://oauth2.googleapis.com/token";
 /// Google Docs API scope
 pub const GOOGLE_DOCS_SCOPE: &str = "https://www.googleapis.com/auth/documents";
 
+/// Google Docs API read-only scope
+pub const GOOGLE_DOCS_READONLY_SCOPE: &str = "https://www.googleapis.com/auth/documents.readonly";
+
 /// JWT expiration time in seconds (1 hour)
 pub const JWT_EXPIRATION_SECS: i64 = 3600;
+
+/// Google Drive API base URL
+pub const GOOGLE_DRIVE_API_URL: &str = "https://www.googleapis.com/drive/v3";
+
+/// Google Drive API scope (full access, needed to file documents into existing folders)
+pub const GOOGLE_DRIVE_SCOPE: &str = "https://www.googleapis.com/auth/drive";
+
+/// Google Drive metadata read-only scope (listing and searching files)
+pub const GOOGLE_DRIVE_METADATA_READONLY_SCOPE: &str =
+    "https://www.googleapis.com/auth/drive.metadata.readonly";
+
+/// MIME type of Google Docs files in Drive
+pub const GOOGLE_DOCS_MIME_TYPE: &str = "application/vnd.google-apps.document";
+
+/// Google Drive read-only scope (downloading and exporting file content)
+pub const GOOGLE_DRIVE_READONLY_SCOPE: &str = "https://www.googleapis.com/auth/drive.readonly";
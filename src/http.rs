@@ -0,0 +1,309 @@
+use crate::config::ConfigFile;
+use crate::tools::GoogleDocsMcpServer;
+use axum::{
+    Router,
+    extract::{Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+};
+use jsonwebtoken::{Algorithm, DecodingKey, Validation, decode};
+use rmcp::ErrorData as McpError;
+use rmcp::model::Extensions;
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_util::sync::CancellationToken;
+
+/// Path the MCP endpoint is served under
+pub const MCP_PATH: &str = "/mcp";
+
+/// Environment variable holding a static bearer token clients must present
+pub const AUTH_TOKEN_ENV: &str = "GOOGLE_DOCS_MCP_AUTH_TOKEN";
+
+/// Environment variable holding an HMAC secret for verifying HS256 client JWTs
+pub const JWT_SECRET_ENV: &str = "GOOGLE_DOCS_MCP_JWT_SECRET";
+
+/// Environment variable holding the path to a PEM public key for verifying RS256/ES256 client JWTs
+pub const JWT_PUBLIC_KEY_ENV: &str = "GOOGLE_DOCS_MCP_JWT_PUBLIC_KEY";
+
+/// Environment variable holding the required `iss` claim of client JWTs
+pub const JWT_ISSUER_ENV: &str = "GOOGLE_DOCS_MCP_JWT_ISSUER";
+
+/// Environment variable holding the required `aud` claim of client JWTs
+pub const JWT_AUDIENCE_ENV: &str = "GOOGLE_DOCS_MCP_JWT_AUDIENCE";
+
+/// How long open connections (such as SSE streams) may keep the server alive after shutdown starts
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+/// How incoming HTTP clients are authenticated
+#[derive(Clone)]
+pub enum HttpAuth {
+    /// Accept every request; only suitable behind another authenticating proxy
+    None,
+    /// Require `Authorization: Bearer <token>` with this exact token
+    BearerToken(String),
+    /// Require one of several tokens, each authenticating a named client identity
+    NamedBearerTokens(Arc<Vec<(String, String)>>),
+    /// Require a bearer JWT that verifies against the given key; its `sub`
+    /// claim is the client identity
+    Jwt(Arc<JwtAuth>),
+}
+
+/// The authenticated identity of an HTTP client, stored in the request extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientIdentity(pub String);
+
+/// Claims read from a verified client JWT
+#[derive(Debug, Deserialize)]
+struct ClientClaims {
+    /// Subject identifying the client
+    sub: String,
+}
+
+/// The identity of the HTTP client that sent an MCP request, if it was authenticated as one
+pub fn client_identity(extensions: &Extensions) -> Option<ClientIdentity> {
+    extensions
+        .get::<axum::http::request::Parts>()
+        .and_then(|parts| parts.extensions.get::<ClientIdentity>())
+        .cloned()
+}
+
+/// Verification settings for client JWTs
+pub struct JwtAuth {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtAuth {
+    /// Verify HS256 tokens signed with a shared secret
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self::with_key(DecodingKey::from_secret(secret), Algorithm::HS256)
+    }
+
+    fn with_key(key: DecodingKey, algorithm: Algorithm) -> Self {
+        let mut validation = Validation::new(algorithm);
+        validation.set_required_spec_claims(&["exp", "sub"]);
+        Self { key, validation }
+    }
+
+    /// Verify RS256 or ES256 tokens against a PEM-encoded public key
+    pub fn from_public_key_pem(pem: &[u8]) -> Result<Self, McpError> {
+        if let Ok(key) = DecodingKey::from_rsa_pem(pem) {
+            return Ok(Self::with_key(key, Algorithm::RS256));
+        }
+        if let Ok(key) = DecodingKey::from_ec_pem(pem) {
+            return Ok(Self::with_key(key, Algorithm::ES256));
+        }
+        Err(McpError::invalid_params(
+            "JWT public key is neither an RSA nor an EC public key in PEM format".to_string(),
+            None,
+        ))
+    }
+
+    /// Require the `iss` claim to equal `issuer`
+    pub fn with_issuer(mut self, issuer: &str) -> Self {
+        self.validation.set_issuer(&[issuer]);
+        self
+    }
+
+    /// Require the `aud` claim to contain `audience`
+    pub fn with_audience(mut self, audience: &str) -> Self {
+        self.validation.set_audience(&[audience]);
+        self
+    }
+
+    fn verify(&self, token: &str) -> Result<ClientClaims, jsonwebtoken::errors::Error> {
+        decode::<ClientClaims>(token, &self.key, &self.validation).map(|data| data.claims)
+    }
+}
+
+impl HttpAuth {
+    /// Read the authentication settings from environment variables.
+    ///
+    /// Returns `Ok(None)` when no authentication is configured, so the caller
+    /// can decide whether running without authentication is acceptable.
+    pub fn from_env() -> Result<Option<Self>, McpError> {
+        let token = non_empty_env(AUTH_TOKEN_ENV);
+        let secret = non_empty_env(JWT_SECRET_ENV);
+        let public_key = non_empty_env(JWT_PUBLIC_KEY_ENV);
+
+        let jwt = match (&secret, &public_key) {
+            (Some(_), Some(_)) => {
+                return Err(McpError::invalid_params(
+                    format!(
+                        "Set only one of {} and {}",
+                        JWT_SECRET_ENV, JWT_PUBLIC_KEY_ENV
+                    ),
+                    None,
+                ));
+            }
+            (Some(secret), None) => Some(JwtAuth::hs256(secret.as_bytes())),
+            (None, Some(path)) => {
+                let pem = std::fs::read(path).map_err(|e| {
+                    McpError::invalid_params(
+                        format!("Failed to read JWT public key {}: {}", path, e),
+                        None,
+                    )
+                })?;
+                Some(JwtAuth::from_public_key_pem(&pem)?)
+            }
+            (None, None) => None,
+        };
+
+        let jwt = jwt.map(|mut jwt| {
+            if let Some(issuer) = non_empty_env(JWT_ISSUER_ENV) {
+                jwt = jwt.with_issuer(&issuer);
+            }
+            if let Some(audience) = non_empty_env(JWT_AUDIENCE_ENV) {
+                jwt = jwt.with_audience(&audience);
+            }
+            jwt
+        });
+
+        match (token, jwt) {
+            (Some(_), Some(_)) => Err(McpError::invalid_params(
+                format!(
+                    "{} cannot be combined with JWT verification",
+                    AUTH_TOKEN_ENV
+                ),
+                None,
+            )),
+            (Some(token), None) => Ok(Some(Self::BearerToken(token))),
+            (None, Some(jwt)) => Ok(Some(Self::Jwt(Arc::new(jwt)))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Read per-client bearer tokens from the configuration file's `http_tokens`
+    pub fn from_config(config: &ConfigFile) -> Result<Option<Self>, McpError> {
+        if config.http_tokens.is_empty() {
+            return Ok(None);
+        }
+
+        let mut tokens = Vec::with_capacity(config.http_tokens.len());
+        for (identity, token) in &config.http_tokens {
+            let value = non_empty_env(&token.token_env).ok_or_else(|| {
+                McpError::invalid_params(
+                    format!(
+                        "HTTP token for \"{}\": environment variable {} is not set",
+                        identity, token.token_env
+                    ),
+                    None,
+                )
+            })?;
+            tokens.push((value, identity.clone()));
+        }
+        Ok(Some(Self::NamedBearerTokens(Arc::new(tokens))))
+    }
+
+    /// Check the bearer token of a request, returning the client identity it proves
+    fn authenticate(&self, bearer: Option<&str>) -> Result<Option<ClientIdentity>, &'static str> {
+        match self {
+            Self::None => Ok(None),
+            Self::BearerToken(expected) => match bearer {
+                Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(None),
+                Some(_) => Err("invalid bearer token"),
+                None => Err("missing bearer token"),
+            },
+            Self::NamedBearerTokens(tokens) => {
+                let token = bearer.ok_or("missing bearer token")?;
+                tokens
+                    .iter()
+                    .find(|(expected, _)| constant_time_eq(token.as_bytes(), expected.as_bytes()))
+                    .map(|(_, identity)| Some(ClientIdentity(identity.clone())))
+                    .ok_or("invalid bearer token")
+            }
+            Self::Jwt(jwt) => {
+                let token = bearer.ok_or("missing bearer token")?;
+                jwt.verify(token)
+                    .map(|claims| Some(ClientIdentity(claims.sub)))
+                    .map_err(|e| {
+                        tracing::debug!("rejected client JWT: {}", e);
+                        "invalid bearer token"
+                    })
+            }
+        }
+    }
+}
+
+/// Build the axum router serving the MCP endpoint behind authentication
+pub fn router(server: GoogleDocsMcpServer, auth: HttpAuth) -> Router {
+    let service = StreamableHttpService::new(
+        move || Ok(server.for_session()),
+        Arc::new(LocalSessionManager::default()),
+        StreamableHttpServerConfig::default(),
+    );
+
+    Router::new()
+        .nest_service(MCP_PATH, service)
+        .layer(middleware::from_fn_with_state(auth, require_auth))
+}
+
+/// Serve MCP over streamable HTTP until `shutdown` is cancelled.
+///
+/// Once shutdown starts, no new connections are accepted and in-flight
+/// requests may finish; connections still open after a grace period
+/// (typically SSE streams) are dropped.
+pub async fn serve(
+    listener: TcpListener,
+    server: GoogleDocsMcpServer,
+    auth: HttpAuth,
+    shutdown: CancellationToken,
+) -> std::io::Result<()> {
+    let app = router(server, auth);
+    let graceful =
+        axum::serve(listener, app).with_graceful_shutdown(shutdown.clone().cancelled_owned());
+
+    tokio::select! {
+        result = graceful => result,
+        _ = async {
+            shutdown.cancelled().await;
+            tokio::time::sleep(SHUTDOWN_GRACE_PERIOD).await;
+        } => {
+            tracing::warn!("closing connections still open after the shutdown grace period");
+            Ok(())
+        }
+    }
+}
+
+/// Reject requests that do not carry valid credentials and record the client identity
+async fn require_auth(State(auth): State<HttpAuth>, mut request: Request, next: Next) -> Response {
+    let bearer = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim);
+
+    match auth.authenticate(bearer) {
+        Ok(identity) => {
+            if let Some(identity) = identity {
+                request.extensions_mut().insert(identity);
+            }
+            next.run(request).await
+        }
+        Err(reason) => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            reason,
+        )
+            .into_response(),
+    }
+}
+
+/// Compare two byte strings without leaking the position of the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn non_empty_env(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.trim().is_empty())
+}
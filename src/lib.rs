@@ -1,8 +1,17 @@
 mod api;
+mod config;
 mod constants;
+pub mod http;
 mod models;
 mod tools;
 
-pub use api::GoogleDocsClient;
+pub use api::{
+    ClientProfiles, CredentialSource, DriveClient, GoogleDocsClient, parse_credentials,
+    validate_credentials,
+};
+pub use config::{
+    CONFIG_PATH_ENV, CONFIRM_DELETE_CHARS_ENV, ConfigFile, DRIVE_FEATURES_ENV, EXPORT_DIR_ENV, DriveFeature, IdentityConfig, ProfileConfig,
+    POLL_INTERVAL_ENV, SHARE_DOMAINS_ENV, ServerOptions, TokenConfig,
+};
 pub use models::*;
 pub use tools::GoogleDocsMcpServer;
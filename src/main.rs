@@ -1,11 +1,46 @@
-use google_docs_mcp_server::{GoogleDocsClient, GoogleDocsMcpServer};
+use clap::{Parser, ValueEnum};
+use google_docs_mcp_server::http::{self, HttpAuth};
+use google_docs_mcp_server::{
+    CONFIG_PATH_ENV, ClientProfiles, ConfigFile, CredentialSource, GoogleDocsClient,
+    GoogleDocsMcpServer, ServerOptions,
+};
 use rmcp::transport::stdio;
-use rmcp::ServiceExt;
+use rmcp::{ErrorData as McpError, ServiceExt};
 use std::env;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use tokio_util::sync::CancellationToken;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+/// MCP server for the Google Docs API
+#[derive(Debug, Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Transport to serve MCP over
+    #[arg(long, value_enum, default_value_t = Transport::Stdio, env = "GOOGLE_DOCS_MCP_TRANSPORT")]
+    transport: Transport,
+
+    /// Address to listen on with the HTTP transport
+    #[arg(long, default_value = "127.0.0.1:8080", env = "GOOGLE_DOCS_MCP_BIND")]
+    bind: SocketAddr,
+
+    /// Serve HTTP without authenticating clients (only behind an authenticating proxy)
+    #[arg(long)]
+    allow_unauthenticated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Transport {
+    /// Serve a single client over stdin/stdout
+    Stdio,
+    /// Serve many clients over streamable HTTP
+    Http,
+}
+
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
     // Initialize tracing (logs to stderr so it doesn't interfere with stdio transport)
     tracing_subscriber::registry()
         .with(
@@ -15,33 +50,189 @@ async fn main() -> anyhow::Result<()> {
         .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr))
         .init();
 
-    // Get service account credentials path from environment
-    let credentials_path = env::var("GOOGLE_SERVICE_ACCOUNT_KEY").unwrap_or_else(|_| {
-        eprintln!("Error: GOOGLE_SERVICE_ACCOUNT_KEY environment variable is required.");
-        eprintln!("Set it to the path of your service account JSON key file.");
-        eprintln!();
-        eprintln!("Example:");
-        eprintln!("  export GOOGLE_SERVICE_ACCOUNT_KEY=/path/to/service-account.json");
-        std::process::exit(1);
-    });
-
-    // Create Google Docs API client
-    let client = GoogleDocsClient::from_json_file(&credentials_path).map_err(|e| {
-        eprintln!("Failed to initialize Google Docs client: {:?}", e);
+    let (profiles, credentials_description, config) = load_profiles(cli.transport).map_err(|e| {
+        eprintln!("Failed to initialize Google Docs client: {}", e.message);
         anyhow::anyhow!("Failed to initialize client")
     })?;
 
+    let options = ServerOptions::from_env().map_err(|e| {
+        eprintln!("Invalid configuration: {}", e.message);
+        anyhow::anyhow!("Invalid configuration")
+    })?;
+    let read_only = options.read_only;
+
     // Create MCP server
-    let server = GoogleDocsMcpServer::new(client);
+    let server = GoogleDocsMcpServer::with_profiles(profiles, options);
 
     eprintln!("Google Docs MCP Server starting...");
-    eprintln!("Using service account credentials from: {}", credentials_path);
+    eprintln!("Using credentials from {}", credentials_description);
+    if read_only {
+        eprintln!("Read-only mode: write tools are disabled");
+    }
+
+    match cli.transport {
+        Transport::Stdio => {
+            // Run with stdio transport
+            let service = server.serve(stdio()).await?;
 
-    // Run with stdio transport
-    let service = server.serve(stdio()).await?;
+            // Wait for service to complete
+            service.waiting().await?;
+        }
+        Transport::Http => {
+            let auth = match http_auth(config.as_ref()) {
+                Ok(Some(auth)) => auth,
+                Ok(None) if cli.allow_unauthenticated => {
+                    eprintln!("Warning: HTTP clients are not authenticated");
+                    HttpAuth::None
+                }
+                Ok(None) => {
+                    eprintln!("Error: the HTTP transport requires client authentication.");
+                    eprintln!("Set one of the following environment variables:");
+                    eprintln!("  {}      a static bearer token", http::AUTH_TOKEN_ENV);
+                    eprintln!("  {}      an HS256 secret for client JWTs", http::JWT_SECRET_ENV);
+                    eprintln!("  {}  a PEM public key file for RS256/ES256 client JWTs", http::JWT_PUBLIC_KEY_ENV);
+                    eprintln!("or pass --allow-unauthenticated when running behind an authenticating proxy.");
+                    std::process::exit(1);
+                }
+                Err(e) => {
+                    eprintln!("Invalid HTTP authentication settings: {}", e.message);
+                    std::process::exit(1);
+                }
+            };
 
-    // Wait for service to complete
-    service.waiting().await?;
+            let maps_identities = config.as_ref().is_some_and(|c| !c.identities.is_empty());
+            if maps_identities && matches!(auth, HttpAuth::None | HttpAuth::BearerToken(_)) {
+                eprintln!(
+                    "Warning: identities are only applied to clients authenticated by http_tokens or JWTs"
+                );
+            }
+
+            let listener = tokio::net::TcpListener::bind(cli.bind).await?;
+            eprintln!(
+                "Serving MCP over HTTP at http://{}{}",
+                listener.local_addr()?,
+                http::MCP_PATH
+            );
+
+            let shutdown = CancellationToken::new();
+            tokio::spawn(shutdown_on_signal(shutdown.clone()));
+            http::serve(listener, server, auth, shutdown).await?;
+            eprintln!("Google Docs MCP Server stopped");
+        }
+    }
 
     Ok(())
 }
+
+/// Cancel `shutdown` on SIGTERM or Ctrl-C
+async fn shutdown_on_signal(shutdown: CancellationToken) {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    eprintln!("Shutting down...");
+    shutdown.cancel();
+}
+
+/// How to configure credentials, shown when none are set
+const MISSING_CREDENTIALS: &str = "credentials are required.
+Set exactly one of the following environment variables:
+  GOOGLE_SERVICE_ACCOUNT_KEY       path to the JSON key file (\"-\" reads stdin)
+  GOOGLE_SERVICE_ACCOUNT_KEY_JSON  the key JSON itself, plain or base64-encoded
+  GOOGLE_SERVICE_ACCOUNT_KEY_FD    a file descriptor to read the key JSON from
+  GOOGLE_DOCS_MCP_CONFIG           a configuration file with credential profiles
+
+Example:
+  export GOOGLE_SERVICE_ACCOUNT_KEY=/path/to/service-account.json";
+
+/// Build the credential profiles from the configuration file, or a single
+/// profile from the credential environment variables
+fn load_profiles(
+    transport: Transport,
+) -> Result<(ClientProfiles, String, Option<ConfigFile>), McpError> {
+    let source = CredentialSource::from_env()?;
+
+    if let Some(config_path) = env::var_os(CONFIG_PATH_ENV) {
+        if source.is_some() {
+            return Err(McpError::invalid_params(
+                format!(
+                    "{} cannot be combined with the GOOGLE_SERVICE_ACCOUNT_KEY* variables; \
+                     configure credentials in the profiles instead",
+                    CONFIG_PATH_ENV
+                ),
+                None,
+            ));
+        }
+        let config_path = PathBuf::from(config_path);
+        let config = ConfigFile::load(&config_path)?;
+        let profiles = ClientProfiles::from_config(&config)?;
+        let description = format!(
+            "profiles {} in {} (default: {})",
+            profiles.names().collect::<Vec<_>>().join(", "),
+            config_path.display(),
+            profiles.default_profile()
+        );
+        return Ok((profiles, description, Some(config)));
+    }
+
+    let Some(source) = source else {
+        return Err(McpError::invalid_params(
+            MISSING_CREDENTIALS.to_string(),
+            None,
+        ));
+    };
+
+    // stdin carries the MCP protocol itself when serving over stdio
+    if source == CredentialSource::Stdin && transport == Transport::Stdio {
+        return Err(McpError::invalid_params(
+            "Credentials cannot be read from stdin while stdin is used by the stdio transport. \
+             Use GOOGLE_SERVICE_ACCOUNT_KEY_JSON or GOOGLE_SERVICE_ACCOUNT_KEY_FD instead."
+                .to_string(),
+            None,
+        ));
+    }
+
+    let client = GoogleDocsClient::from_source(&source)?;
+    Ok((ClientProfiles::single(client), source.describe(), None))
+}
+
+/// Combine the HTTP authentication settings from the environment and the
+/// configuration file's per-client tokens
+fn http_auth(config: Option<&ConfigFile>) -> Result<Option<HttpAuth>, McpError> {
+    let from_env = HttpAuth::from_env()?;
+    let from_config = match config {
+        Some(config) => HttpAuth::from_config(config)?,
+        None => None,
+    };
+    match (from_env, from_config) {
+        (Some(_), Some(_)) => Err(McpError::invalid_params(
+            "http_tokens in the configuration file cannot be combined with \
+             GOOGLE_DOCS_MCP_AUTH_TOKEN or JWT verification"
+                .to_string(),
+            None,
+        )),
+        (auth, None) | (None, auth) => Ok(auth),
+    }
+}
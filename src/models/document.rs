@@ -1,8 +1,9 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// Output format for responses
-#[derive(Debug, Default, Clone, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum ResponseFormat {
     /// Human-readable markdown format
@@ -22,6 +23,9 @@ pub enum DocumentRequest {
         text: String,
         /// The index where to insert (1 = beginning of document body)
         index: i32,
+        /// The tab the index is in (defaults to the tab in the document URL, then the first tab)
+        #[serde(default, rename = "tabId", skip_serializing_if = "Option::is_none")]
+        tab_id: Option<String>,
     },
     /// Delete content in a range
     DeleteContentRange {
@@ -31,6 +35,9 @@ pub enum DocumentRequest {
         /// End index of the range to delete
         #[serde(rename = "endIndex")]
         end_index: i32,
+        /// The tab the range is in (defaults to the tab in the document URL, then the first tab)
+        #[serde(default, rename = "tabId", skip_serializing_if = "Option::is_none")]
+        tab_id: Option<String>,
     },
     /// Replace all occurrences of text
     ReplaceAllText {
@@ -43,6 +50,23 @@ pub enum DocumentRequest {
         /// Whether to match case
         #[serde(default, rename = "matchCase")]
         match_case: bool,
+        /// Only replace in this tab (defaults to the tab in the document URL, then all tabs)
+        #[serde(default, rename = "tabId", skip_serializing_if = "Option::is_none")]
+        tab_id: Option<String>,
+    },
+    /// Replace every match of a regular expression, expanding capture groups
+    RegexReplace {
+        /// The regular expression to find (Rust regex syntax)
+        pattern: String,
+        /// The replacement text; `$1` or `${name}` insert a capture group and `$$` a literal `$`
+        replacement: String,
+        /// Whether to match case
+        #[serde(default, rename = "matchCase")]
+        match_case: bool,
+        /// Only replace in this tab and its child tabs (defaults to the tab in the document
+        /// URL, then all tabs)
+        #[serde(default, rename = "tabId", skip_serializing_if = "Option::is_none")]
+        tab_id: Option<String>,
     },
 }
 
@@ -114,6 +138,27 @@ pub struct DocumentTab {
     /// The body content of the tab
     #[serde(default)]
     pub body: Option<DocumentBody>,
+
+    /// Headers of the tab by header ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, DocumentSegment>,
+
+    /// Footers of the tab by footer ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub footers: BTreeMap<String, DocumentSegment>,
+
+    /// Footnotes of the tab by footnote ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub footnotes: BTreeMap<String, DocumentSegment>,
+}
+
+/// A header, footer or footnote, listed under its segment ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DocumentSegment {
+    /// The content of the segment
+    #[serde(default)]
+    pub content: Vec<StructuralElement>,
 }
 
 /// Document body structure
@@ -140,6 +185,50 @@ pub struct StructuralElement {
     /// Paragraph content
     #[serde(default)]
     pub paragraph: Option<Paragraph>,
+
+    /// Table content
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table: Option<Table>,
+
+    /// Table of contents, whose text is generated from the headings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub table_of_contents: Option<TableOfContents>,
+}
+
+/// A table in the document
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    /// The rows of the table
+    #[serde(default)]
+    pub table_rows: Vec<TableRow>,
+}
+
+/// A row of a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRow {
+    /// The cells of the row
+    #[serde(default)]
+    pub table_cells: Vec<TableCell>,
+}
+
+/// A cell of a table
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableCell {
+    /// The content of the cell
+    #[serde(default)]
+    pub content: Vec<StructuralElement>,
+}
+
+/// A table of contents
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableOfContents {
+    /// The content of the table of contents
+    #[serde(default)]
+    pub content: Vec<StructuralElement>,
 }
 
 /// A paragraph in the document
@@ -149,6 +238,23 @@ pub struct Paragraph {
     /// The paragraph elements
     #[serde(default)]
     pub elements: Vec<ParagraphElement>,
+
+    /// The paragraph's style
+    #[serde(default)]
+    pub paragraph_style: Option<ParagraphStyle>,
+}
+
+/// Style of a paragraph
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ParagraphStyle {
+    /// Named style such as NORMAL_TEXT, TITLE or HEADING_1
+    #[serde(default)]
+    pub named_style_type: Option<String>,
+
+    /// ID of a heading paragraph, used in `#heading=` links
+    #[serde(default)]
+    pub heading_id: Option<String>,
 }
 
 /// An element within a paragraph
@@ -181,12 +287,32 @@ pub struct TextRun {
 // Google Docs API Request Models
 // =============================================================================
 
+/// Request body for creating a document
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateDocumentRequest {
+    /// The title of the new document
+    pub title: String,
+}
+
 /// Request body for batch updating a document
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BatchUpdateRequest {
     /// The list of requests to apply
     pub requests: Vec<GoogleDocsRequest>,
+
+    /// Conditions the document must meet for the requests to apply
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub write_control: Option<WriteControl>,
+}
+
+/// Write control for a batch update
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteControl {
+    /// Fail unless the document is still at this revision
+    pub required_revision_id: String,
 }
 
 /// A single request in a batch update
@@ -222,6 +348,12 @@ pub struct InsertTextRequest {
 pub struct Location {
     /// The index in the document
     pub index: i32,
+    /// The tab the index is in (the first tab when omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_id: Option<String>,
+    /// The header, footer or footnote the index is in (the body when omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_id: Option<String>,
 }
 
 /// Delete content range request
@@ -240,6 +372,12 @@ pub struct Range {
     pub start_index: i32,
     /// End index
     pub end_index: i32,
+    /// The tab the range is in (the first tab when omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tab_id: Option<String>,
+    /// The header, footer or footnote the range is in (the body when omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segment_id: Option<String>,
 }
 
 /// Replace all text request
@@ -250,6 +388,17 @@ pub struct ReplaceAllTextRequest {
     pub contains_text: ContainsText,
     /// The replacement text
     pub replace_text: String,
+    /// The tabs to replace in (all tabs when omitted)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tabs_criteria: Option<TabsCriteria>,
+}
+
+/// Tabs a request applies to
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TabsCriteria {
+    /// IDs of the tabs
+    pub tab_ids: Vec<String>,
 }
 
 /// Text search criteria
@@ -275,7 +424,7 @@ pub struct BatchUpdateResponse {
 }
 
 // =============================================================================
-// Service Account Credentials
+// Credentials
 // =============================================================================
 
 /// Service account credentials from JSON key file
@@ -307,6 +456,47 @@ pub struct ServiceAccountCredentials {
     pub token_uri: String,
 }
 
+/// OAuth2 user credentials with a refresh token (as written by
+/// `gcloud auth application-default login`)
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthorizedUserCredentials {
+    /// The type of credentials (should be "authorized_user")
+    #[serde(rename = "type")]
+    pub credential_type: String,
+
+    /// The OAuth client ID
+    pub client_id: String,
+
+    /// The OAuth client secret
+    pub client_secret: String,
+
+    /// The long-lived refresh token
+    pub refresh_token: String,
+
+    /// The token URI
+    #[serde(default)]
+    pub token_uri: Option<String>,
+}
+
+/// Credentials a client can authenticate with
+#[derive(Debug, Clone)]
+pub enum Credentials {
+    /// A service account key, signing its own JWT assertions
+    ServiceAccount(ServiceAccountCredentials),
+    /// A user's OAuth refresh token
+    AuthorizedUser(AuthorizedUserCredentials),
+}
+
+impl Credentials {
+    /// The identity these credentials act as, if known without a network call
+    pub fn client_email(&self) -> Option<&str> {
+        match self {
+            Self::ServiceAccount(creds) => Some(&creds.client_email),
+            Self::AuthorizedUser(_) => None,
+        }
+    }
+}
+
 /// OAuth2 token response
 #[derive(Debug, Deserialize)]
 pub struct TokenResponse {
@@ -382,6 +572,7 @@ mod tests {
         let request = DocumentRequest::InsertText {
             text: "Hello, World!".to_string(),
             index: 1,
+            tab_id: None,
         };
 
         // When: Serializing to JSON
@@ -398,6 +589,7 @@ mod tests {
         let request = DocumentRequest::DeleteContentRange {
             start_index: 5,
             end_index: 10,
+            tab_id: None,
         };
 
         // When: Serializing to JSON
@@ -415,6 +607,7 @@ mod tests {
             find_text: "old".to_string(),
             replace_text: "new".to_string(),
             match_case: true,
+            tab_id: None,
         };
 
         // When: Serializing to JSON
@@ -442,6 +635,30 @@ mod tests {
         }
     }
 
+    #[test]
+    fn document_request_regex_replace_deserializes_with_defaults() {
+        // Given: JSON with only pattern and replacement (camelCase format)
+        let json = r#"{"regexReplace":{"pattern":"v(\\d+)","replacement":"version $1"}}"#;
+
+        // When: Deserializing the request
+        let request: DocumentRequest = serde_json::from_str(json).unwrap();
+
+        // Then: match_case should default to false and no tab should be set
+        if let DocumentRequest::RegexReplace {
+            pattern,
+            match_case,
+            tab_id,
+            ..
+        } = request
+        {
+            assert_eq!(pattern, r"v(\d+)");
+            assert!(!match_case);
+            assert_eq!(tab_id, None);
+        } else {
+            panic!("Expected RegexReplace variant");
+        }
+    }
+
     // -------------------------------------------------------------------------
     // Document Model Tests
     // -------------------------------------------------------------------------
@@ -672,7 +889,11 @@ mod tests {
         let request = GoogleDocsRequest {
             insert_text: Some(InsertTextRequest {
                 text: "Hello".to_string(),
-                location: Location { index: 1 },
+                location: Location {
+                    index: 1,
+                    tab_id: None,
+                    segment_id: None,
+                },
             }),
             delete_content_range: None,
             replace_all_text: None,
@@ -695,12 +916,17 @@ mod tests {
                 GoogleDocsRequest {
                     insert_text: Some(InsertTextRequest {
                         text: "New text".to_string(),
-                        location: Location { index: 1 },
+                        location: Location {
+                            index: 1,
+                            tab_id: None,
+                            segment_id: None,
+                        },
                     }),
                     delete_content_range: None,
                     replace_all_text: None,
                 },
             ],
+            write_control: None,
         };
 
         // When: Serializing to JSON
@@ -720,6 +946,7 @@ mod tests {
                 match_case: true,
             },
             replace_text: "replacement".to_string(),
+            tabs_criteria: None,
         };
 
         // When: Serializing to JSON
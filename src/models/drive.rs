@@ -0,0 +1,349 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// File format a document can be exported to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// PDF document
+    Pdf,
+    /// Microsoft Word document
+    Docx,
+    /// OpenDocument text
+    Odt,
+    /// HTML page
+    Html,
+    /// EPUB e-book
+    Epub,
+    /// Plain text
+    Txt,
+}
+
+impl ExportFormat {
+    /// The MIME type requested from files.export
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "application/pdf",
+            ExportFormat::Docx => {
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document"
+            }
+            ExportFormat::Odt => "application/vnd.oasis.opendocument.text",
+            ExportFormat::Html => "text/html",
+            ExportFormat::Epub => "application/epub+zip",
+            ExportFormat::Txt => "text/plain",
+        }
+    }
+
+    /// The file extension, without the dot
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Pdf => "pdf",
+            ExportFormat::Docx => "docx",
+            ExportFormat::Odt => "odt",
+            ExportFormat::Html => "html",
+            ExportFormat::Epub => "epub",
+            ExportFormat::Txt => "txt",
+        }
+    }
+
+    /// Whether the exported bytes are text rather than a binary format
+    pub fn is_text(self) -> bool {
+        matches!(self, ExportFormat::Html | ExportFormat::Txt)
+    }
+}
+
+// =============================================================================
+// Google Drive API Response Models
+// =============================================================================
+
+/// File metadata returned by the Drive API (only the requested fields are set)
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFile {
+    /// The file ID (the document ID for Google Docs)
+    pub id: String,
+
+    /// The file name (the document title for Google Docs)
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// IDs of the folders containing the file
+    #[serde(default)]
+    pub parents: Vec<String>,
+
+    /// The file's MIME type
+    #[serde(default)]
+    pub mime_type: Option<String>,
+
+    /// Link for opening the file in a browser
+    #[serde(default)]
+    pub web_view_link: Option<String>,
+
+    /// Last modification time (RFC 3339)
+    #[serde(default)]
+    pub modified_time: Option<String>,
+
+    /// Owners of the file (empty for files in shared drives)
+    #[serde(default)]
+    pub owners: Vec<DriveUser>,
+}
+
+/// A Drive user as embedded in file metadata
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveUser {
+    /// The user's display name
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// The user's email address
+    #[serde(default)]
+    pub email_address: Option<String>,
+}
+
+impl DriveUser {
+    /// The best available name for display
+    pub fn label(&self) -> &str {
+        self.display_name
+            .as_deref()
+            .or(self.email_address.as_deref())
+            .unwrap_or("unknown")
+    }
+}
+
+/// A page of results from files.list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DriveFileList {
+    /// Files on this page
+    #[serde(default)]
+    pub files: Vec<DriveFile>,
+
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+/// A comment on a file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Comment {
+    /// The comment ID
+    pub id: String,
+
+    /// The plain text content of the comment
+    #[serde(default)]
+    pub content: String,
+
+    /// The author of the comment
+    #[serde(default)]
+    pub author: Option<DriveUser>,
+
+    /// Creation time (RFC 3339)
+    #[serde(default)]
+    pub created_time: Option<String>,
+
+    /// Whether the comment has been resolved by one of its replies
+    #[serde(default)]
+    pub resolved: bool,
+
+    /// The document text the comment is anchored to
+    #[serde(default)]
+    pub quoted_file_content: Option<QuotedFileContent>,
+
+    /// Replies in chronological order
+    #[serde(default)]
+    pub replies: Vec<CommentReply>,
+}
+
+/// The file content a comment refers to
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotedFileContent {
+    /// MIME type of the quoted content
+    #[serde(default)]
+    pub mime_type: Option<String>,
+
+    /// The quoted content itself
+    #[serde(default)]
+    pub value: String,
+}
+
+/// A reply to a comment
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentReply {
+    /// The reply ID
+    pub id: String,
+
+    /// The plain text content of the reply (empty for bare actions)
+    #[serde(default)]
+    pub content: String,
+
+    /// The author of the reply
+    #[serde(default)]
+    pub author: Option<DriveUser>,
+
+    /// Creation time (RFC 3339)
+    #[serde(default)]
+    pub created_time: Option<String>,
+
+    /// "resolve" or "reopen" when the reply changed the comment's state
+    #[serde(default)]
+    pub action: Option<String>,
+}
+
+/// A page of results from comments.list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommentList {
+    /// Comments on this page
+    #[serde(default)]
+    pub comments: Vec<Comment>,
+
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+/// A revision of a file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Revision {
+    /// The revision ID
+    pub id: String,
+
+    /// When the revision was saved (RFC 3339)
+    #[serde(default)]
+    pub modified_time: Option<String>,
+
+    /// The user who made the revision
+    #[serde(default)]
+    pub last_modifying_user: Option<DriveUser>,
+
+    /// Download links for the revision's content, keyed by MIME type
+    #[serde(default)]
+    pub export_links: BTreeMap<String, String>,
+}
+
+/// A page of results from revisions.list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevisionList {
+    /// Revisions on this page, oldest first
+    #[serde(default)]
+    pub revisions: Vec<Revision>,
+
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+/// A permission granting a user, group, domain or anyone access to a file
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    /// The permission ID
+    pub id: String,
+
+    /// "user", "group", "domain" or "anyone"
+    #[serde(rename = "type", default)]
+    pub permission_type: String,
+
+    /// "owner", "organizer", "fileOrganizer", "writer", "commenter" or "reader"
+    #[serde(default)]
+    pub role: String,
+
+    /// Email address of the user or group
+    #[serde(default)]
+    pub email_address: Option<String>,
+
+    /// The domain, for domain permissions
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// Display name of the user, group or domain
+    #[serde(default)]
+    pub display_name: Option<String>,
+}
+
+/// A page of results from permissions.list
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionList {
+    /// Permissions on this page
+    #[serde(default)]
+    pub permissions: Vec<Permission>,
+
+    /// Token for the next page, absent on the last page
+    #[serde(default)]
+    pub next_page_token: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drive_file_deserializes_partial_fields() {
+        // Given: A files.update response limited to id and parents
+        let json = r#"{"id": "doc-123", "parents": ["folder-1"]}"#;
+
+        // When: Deserializing
+        let file: DriveFile = serde_json::from_str(json).unwrap();
+
+        // Then: Missing fields should default
+        assert_eq!(file.id, "doc-123");
+        assert_eq!(file.parents, vec!["folder-1".to_string()]);
+        assert!(file.name.is_none());
+    }
+
+    #[test]
+    fn drive_file_list_deserializes_owners_and_page_token() {
+        // Given: A files.list response with one document and another page
+        let json = r#"{
+            "nextPageToken": "page-2",
+            "files": [{
+                "id": "doc-123",
+                "name": "RFC: Search",
+                "modifiedTime": "2024-05-01T10:00:00.000Z",
+                "webViewLink": "https://docs.google.com/document/d/doc-123/edit",
+                "owners": [{"displayName": "Alice", "emailAddress": "alice@example.com"}]
+            }]
+        }"#;
+
+        // When: Deserializing
+        let list: DriveFileList = serde_json::from_str(json).unwrap();
+
+        // Then: The file metadata and pagination token should be available
+        assert_eq!(list.next_page_token.as_deref(), Some("page-2"));
+        assert_eq!(list.files[0].name.as_deref(), Some("RFC: Search"));
+        assert_eq!(
+            list.files[0].owners[0].email_address.as_deref(),
+            Some("alice@example.com")
+        );
+    }
+
+    #[test]
+    fn comment_deserializes_quote_and_resolving_reply() {
+        // Given: A resolved comment anchored to quoted text
+        let json = r#"{
+            "id": "c1",
+            "content": "Is this still accurate?",
+            "author": {"displayName": "Alice"},
+            "resolved": true,
+            "quotedFileContent": {"mimeType": "text/html", "value": "launch in Q3"},
+            "replies": [{"id": "r1", "content": "", "action": "resolve"}]
+        }"#;
+
+        // When: Deserializing
+        let comment: Comment = serde_json::from_str(json).unwrap();
+
+        // Then: The anchor, state and reply action should be available
+        assert!(comment.resolved);
+        assert_eq!(comment.quoted_file_content.unwrap().value, "launch in Q3");
+        assert_eq!(comment.replies[0].action.as_deref(), Some("resolve"));
+        assert_eq!(comment.author.unwrap().label(), "Alice");
+    }
+}
@@ -1,3 +1,9 @@
 mod document;
+mod drive;
+mod output;
+mod reference;
 
 pub use document::*;
+pub use drive::*;
+pub use output::*;
+pub use reference::*;
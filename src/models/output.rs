@@ -0,0 +1,527 @@
+use super::ExportFormat;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// =============================================================================
+// Structured Tool Output
+// =============================================================================
+
+/// Structured result of getting a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DocumentOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The document title
+    pub title: String,
+
+    /// Link to open the document in Google Docs
+    pub url: String,
+
+    /// The revision ID of the document
+    pub revision_id: Option<String>,
+
+    /// Plain text of the document, all tabs included; only the selected
+    /// sections or the current page when reading part of the document
+    pub content: String,
+
+    /// The document's tabs; a document without tabs has a single tab with no ID
+    pub tabs: Vec<TabOutput>,
+
+    /// Comments on the document, when requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments: Option<Vec<CommentOutput>>,
+
+    /// Whether the document has more comments than could be fetched, when
+    /// comments were requested; the newest ones are then missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comments_truncated: Option<bool>,
+
+    /// Headings of the document, when the outline was requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub outline: Option<Vec<HeadingOutput>>,
+
+    /// Which paragraphs this response holds, when reading in pages
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub page: Option<PageOutput>,
+}
+
+/// A heading in a document's outline
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HeadingOutput {
+    /// Heading level, 1 for HEADING_1 through 6 for HEADING_6
+    pub level: u8,
+
+    /// Text of the heading
+    pub text: String,
+
+    /// The heading ID, as in `#heading=` links
+    pub heading_id: Option<String>,
+
+    /// The tab containing the heading
+    pub tab_id: Option<String>,
+
+    /// Index of the heading's first character
+    pub start_index: Option<i32>,
+
+    /// Index just past the end of the heading's section, which runs to the
+    /// next heading of the same or a higher level or the end of the tab
+    pub end_index: Option<i32>,
+}
+
+/// Structured result of getting a document's outline
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OutlineOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The document title
+    pub title: String,
+
+    /// The revision ID of the document
+    pub revision_id: Option<String>,
+
+    /// The document's headings, in order
+    pub headings: Vec<HeadingOutput>,
+}
+
+/// Structured result of searching a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct FindOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The revision ID of the document the indexes refer to
+    pub revision_id: Option<String>,
+
+    /// Number of matches in the document, including any beyond `matches`
+    pub total_matches: usize,
+
+    /// The matches, in document order
+    pub matches: Vec<MatchOutput>,
+}
+
+/// A match found in a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct MatchOutput {
+    /// The tab containing the match
+    pub tab_id: Option<String>,
+
+    /// The header, footer or footnote containing the match, if it is not in the body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub segment_id: Option<String>,
+
+    /// Index of the match's first character
+    pub start_index: i32,
+
+    /// Index just past the match's last character
+    pub end_index: i32,
+
+    /// The matched text
+    pub text: String,
+
+    /// Text just before the match
+    pub before: String,
+
+    /// Text just after the match
+    pub after: String,
+
+    /// Text of the closest heading above the match
+    pub heading: Option<String>,
+}
+
+/// Structured result of getting one section of a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SectionOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The revision ID of the document
+    pub revision_id: Option<String>,
+
+    /// The heading the section starts with
+    pub heading: HeadingOutput,
+
+    /// Plain text of the section, heading included
+    pub content: String,
+
+    /// Paragraphs of the section, heading included
+    pub paragraphs: Vec<ParagraphOutput>,
+}
+
+/// Position of a page within a document read in pages
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PageOutput {
+    /// Position of the page's first paragraph, counting from 0
+    pub first_paragraph: usize,
+
+    /// Number of paragraphs in this page
+    pub paragraphs: usize,
+
+    /// Number of paragraphs in the document, or in the selected sections
+    pub total_paragraphs: usize,
+
+    /// Pass as `cursor` to read the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// A tab of a document with its paragraphs
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TabOutput {
+    /// The tab ID
+    pub tab_id: Option<String>,
+
+    /// The tab title
+    pub title: Option<String>,
+
+    /// Position of the tab among its siblings
+    pub index: Option<i32>,
+
+    /// Paragraphs in the tab's body
+    pub paragraphs: Vec<ParagraphOutput>,
+
+    /// Tabs nested under this tab
+    pub child_tabs: Vec<TabOutput>,
+}
+
+/// A paragraph of a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ParagraphOutput {
+    /// Index of the paragraph's first character
+    pub start_index: Option<i32>,
+
+    /// Index just past the paragraph's last character, including its newline
+    pub end_index: Option<i32>,
+
+    /// Named style such as NORMAL_TEXT, TITLE or HEADING_1
+    pub style: Option<String>,
+
+    /// Text of the paragraph without its trailing newline
+    pub text: String,
+}
+
+/// A comment on a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommentOutput {
+    /// The comment ID
+    pub comment_id: String,
+
+    /// The comment text
+    pub content: String,
+
+    /// Name or email address of the author
+    pub author: Option<String>,
+
+    /// When the comment was created (RFC 3339)
+    pub created_time: Option<String>,
+
+    /// Whether the comment has been resolved
+    pub resolved: bool,
+
+    /// The document text the comment refers to
+    pub quoted_text: Option<String>,
+
+    /// Replies to the comment, oldest first
+    pub replies: Vec<CommentReplyOutput>,
+}
+
+/// A reply to a comment
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommentReplyOutput {
+    /// The reply ID
+    pub reply_id: String,
+
+    /// The reply text
+    pub content: String,
+
+    /// Name or email address of the author
+    pub author: Option<String>,
+
+    /// When the reply was created (RFC 3339)
+    pub created_time: Option<String>,
+
+    /// "resolve" or "reopen" when the reply changed the comment's state
+    pub action: Option<String>,
+}
+
+/// Structured result of creating a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CreateDocumentOutput {
+    /// The new document's ID
+    pub document_id: String,
+
+    /// The document title
+    pub title: String,
+
+    /// Link to open the document in Google Docs
+    pub url: String,
+
+    /// The Drive folder the document was moved into
+    pub folder_id: Option<String>,
+
+    /// The revision ID of the new document
+    pub revision_id: Option<String>,
+}
+
+/// Structured result of updating a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateDocumentOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// Number of operations applied
+    pub operations_count: usize,
+
+    /// Whether all operations were applied
+    pub success: bool,
+
+    /// One reply per operation, in request order
+    pub replies: Vec<OperationReply>,
+}
+
+/// The result of one update operation, from the batchUpdate reply
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OperationReply {
+    /// The operation: insertText, deleteContentRange or replaceAllText
+    pub operation: String,
+
+    /// Occurrences replaced, for replaceAllText
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrences_changed: Option<i64>,
+}
+
+/// Structured result of listing the comments on a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CommentsOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The comments, oldest first
+    pub comments: Vec<CommentOutput>,
+
+    /// Whether the document has more comments than could be fetched; the
+    /// newest ones are then missing
+    pub truncated: bool,
+}
+
+/// Structured result of replying to or resolving a comment
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PostReplyOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The comment replied to
+    pub comment_id: String,
+
+    /// The new reply
+    pub reply: CommentReplyOutput,
+}
+
+/// Structured result of searching for documents
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchOutput {
+    /// The documents on this page of results
+    pub documents: Vec<SearchResultOutput>,
+
+    /// Pass as `page_token` to get the next page; absent on the last page
+    pub next_page_token: Option<String>,
+}
+
+/// A document found by a search
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SearchResultOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The document title
+    pub title: Option<String>,
+
+    /// Link to open the document in Google Docs
+    pub url: String,
+
+    /// When the document was last modified (RFC 3339)
+    pub modified_time: Option<String>,
+
+    /// Email addresses of the document's owners
+    pub owners: Vec<String>,
+}
+
+/// Structured result of creating a document from a template
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TemplateOutput {
+    /// The new document's ID
+    pub document_id: String,
+
+    /// The document title
+    pub title: String,
+
+    /// Link to open the document in Google Docs
+    pub url: String,
+
+    /// Occurrences replaced for each placeholder marker; 0 means it was not found
+    pub occurrences_changed: BTreeMap<String, i64>,
+}
+
+/// Structured result of exporting a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ExportOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The export format
+    pub format: ExportFormat,
+
+    /// MIME type of the exported content
+    pub mime_type: String,
+
+    /// Size of the export in bytes
+    pub size: usize,
+
+    /// The file written, when exporting to the export directory; otherwise the
+    /// content is attached as an embedded resource
+    pub path: Option<String>,
+}
+
+/// Structured result of listing a document's revisions
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevisionsOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The revisions, most recent first
+    pub revisions: Vec<RevisionOutput>,
+
+    /// Whether the document has more revisions than could be fetched; the
+    /// newest ones are then missing
+    pub truncated: bool,
+}
+
+/// A saved revision of a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevisionOutput {
+    /// The revision ID
+    pub revision_id: String,
+
+    /// When the revision was saved (RFC 3339)
+    pub modified_time: Option<String>,
+
+    /// Name or email address of the user who made the revision
+    pub modified_by: Option<String>,
+
+    /// Whether Google kept the revision's content, so it can be read or compared
+    pub content_available: bool,
+}
+
+/// Structured result of getting a past revision
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevisionContentOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The revision
+    pub revision: RevisionOutput,
+
+    /// Plain text of the revision
+    pub content: String,
+}
+
+/// Structured result of comparing two versions of a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DiffOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The version compared from, e.g. "revision 12" or "snapshot"
+    pub from: String,
+
+    /// The version compared to, e.g. "revision 15" or "current"
+    pub to: String,
+
+    /// Number of paragraphs added
+    pub paragraphs_added: usize,
+
+    /// Number of paragraphs removed
+    pub paragraphs_removed: usize,
+
+    /// Unified diff with one line per paragraph; empty when nothing changed
+    pub diff: String,
+}
+
+/// Structured result of listing who has access to a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PermissionsOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The document's permissions
+    pub permissions: Vec<PermissionOutput>,
+
+    /// Whether the document has more permissions than could be fetched
+    pub truncated: bool,
+}
+
+/// Access granted to a user, group, domain or anyone with the link
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PermissionOutput {
+    /// The permission ID
+    pub permission_id: String,
+
+    /// Who is granted access: user, group, domain or anyone
+    #[serde(rename = "type")]
+    pub permission_type: String,
+
+    /// The access level, such as reader, commenter, writer or owner
+    pub role: String,
+
+    /// Email address of the user or group
+    pub email_address: Option<String>,
+
+    /// The domain, for domain permissions
+    pub domain: Option<String>,
+
+    /// Display name of the grantee
+    pub display_name: Option<String>,
+}
+
+/// Structured result of sharing a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShareOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The permission created
+    pub permission: PermissionOutput,
+}
+
+/// Structured result of revoking access to a document
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RevokeOutput {
+    /// The document ID
+    pub document_id: String,
+
+    /// The permission removed
+    pub permission_id: String,
+
+    /// Whether the permission was removed
+    pub revoked: bool,
+}
+
+/// Structured result of reporting the account the server acts as
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WhoamiOutput {
+    /// "service_account" or "authorized_user"
+    pub credential_type: String,
+
+    /// The service account's email address
+    pub client_email: Option<String>,
+
+    /// The user the service account impersonates
+    pub subject: Option<String>,
+
+    /// The address documents must be shared with to be accessible
+    pub share_with: Option<String>,
+
+    /// OAuth scopes the server requests
+    pub scopes: Vec<String>,
+}
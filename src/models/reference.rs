@@ -0,0 +1,309 @@
+use super::ResponseFormat;
+use reqwest::Url;
+
+/// Prefix of the server's own document resource URIs
+pub const RESOURCE_URI_PREFIX: &str = "gdocs://document/";
+
+/// Path segments naming the Google editors that can appear in a link, with a
+/// readable name for the ones that are not Docs
+const EDITORS: &[(&str, Option<&str>)] = &[
+    ("document", None),
+    ("spreadsheets", Some("Google Sheets")),
+    ("presentation", Some("Google Slides")),
+    ("forms", Some("Google Forms")),
+    ("drawings", Some("Google Drawings")),
+];
+
+/// A document named by ID, Google Docs URL or `gdocs://` resource URI,
+/// together with the tab and heading a link points at and the rendering a
+/// resource URI asks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DocumentReference {
+    /// The document ID
+    pub document_id: String,
+    /// Tab from the `tab` query parameter of a URL or the path of a resource URI
+    pub tab_id: Option<String>,
+    /// Heading from a `#heading=` anchor
+    pub heading_id: Option<String>,
+    /// Rendering from the `?format=` query of a resource URI; markdown by default
+    pub format: ResponseFormat,
+}
+
+impl DocumentReference {
+    /// Parse a bare document ID, a Google Docs or Drive URL, or a
+    /// `gdocs://document/{id}[/tab/{tabId}][?format=markdown|json]` URI
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err("Document ID cannot be empty".to_string());
+        }
+
+        if let Some(rest) = input.strip_prefix(RESOURCE_URI_PREFIX) {
+            return parse_resource_uri(input, rest);
+        }
+        if input.contains("://")
+            || input.starts_with("docs.google.com/")
+            || input.starts_with("drive.google.com/")
+        {
+            return parse_url(input);
+        }
+
+        if !is_document_id(input) {
+            return Err(format!(
+                "\"{}\" is neither a document ID nor a Google Docs URL",
+                input
+            ));
+        }
+        Ok(Self {
+            document_id: input.to_string(),
+            tab_id: None,
+            heading_id: None,
+            format: ResponseFormat::default(),
+        })
+    }
+
+    /// Whether `input` is one of the server's own resource URIs rather than
+    /// a document ID or Google Docs URL
+    pub fn is_resource_uri(input: &str) -> bool {
+        input.trim().starts_with(RESOURCE_URI_PREFIX)
+    }
+
+    /// The resource URI of a document's markdown rendering
+    pub fn resource_uri(document_id: &str) -> String {
+        format!("{}{}", RESOURCE_URI_PREFIX, document_id)
+    }
+
+    /// Parse a reference for a tool that acts on the whole document, such as
+    /// sharing, comments, revisions or export. The tab and heading a link
+    /// points at do not apply to these and are ignored.
+    pub fn parse_document_id(input: &str) -> Result<String, String> {
+        Self::parse(input).map(|reference| reference.document_id)
+    }
+}
+
+/// Document IDs are URL-safe base64: letters, digits, `-` and `_`
+fn is_document_id(value: &str) -> bool {
+    !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn parse_resource_uri(input: &str, rest: &str) -> Result<DocumentReference, String> {
+    let (path, query) = match rest.split_once('?') {
+        Some((path, query)) => (path, Some(query)),
+        None => (rest, None),
+    };
+    let format = match query {
+        None | Some("") | Some("format=markdown") => ResponseFormat::Markdown,
+        Some("format=json") => ResponseFormat::Json,
+        Some(query) => {
+            return Err(format!(
+                "Unsupported query \"{}\" in {}; use format=markdown or format=json",
+                query, input
+            ));
+        }
+    };
+
+    let segments: Vec<&str> = path.split('/').collect();
+    let (document_id, tab_id) = match segments.as_slice() {
+        [document_id] => (*document_id, None),
+        [document_id, "tab", tab_id] if !tab_id.is_empty() => (*document_id, Some(*tab_id)),
+        _ => return Err(format!("Unknown resource URI {}", input)),
+    };
+    if !is_document_id(document_id) {
+        return Err(format!("Missing document ID in {}", input));
+    }
+
+    Ok(DocumentReference {
+        document_id: document_id.to_string(),
+        tab_id: tab_id.map(str::to_string),
+        heading_id: None,
+        format,
+    })
+}
+
+fn parse_url(input: &str) -> Result<DocumentReference, String> {
+    let url = if input.contains("://") {
+        Url::parse(input)
+    } else {
+        Url::parse(&format!("https://{}", input))
+    }
+    .map_err(|e| format!("Invalid URL {}: {}", input, e))?;
+
+    let segments: Vec<&str> = url
+        .path_segments()
+        .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+        .unwrap_or_default();
+
+    let document_id = match url.host_str() {
+        // Workspace links put `a/<domain>` and multi-account links `u/<n>`
+        // around the editor name, so look for the editor and then `d/<id>`
+        Some("docs.google.com") => {
+            let editor = segments.iter().enumerate().find_map(|(position, segment)| {
+                EDITORS
+                    .iter()
+                    .find(|(name, _)| name == segment)
+                    .map(|(_, product)| (position, *product))
+            });
+            match editor {
+                Some((_, Some(product))) => {
+                    return Err(format!(
+                        "{} is a {} link; these tools only work with Google Docs documents",
+                        input, product
+                    ));
+                }
+                Some((position, None)) => id_after_d(&segments[position..]),
+                None => None,
+            }
+        }
+        // Drive links are `file/d/<id>/view` or `open?id=<id>`
+        Some("drive.google.com") => id_after_d(&segments).or_else(|| query_value(&url, "id")),
+        _ => {
+            return Err(format!("{} is not a Google Docs URL", input));
+        }
+    };
+
+    let document_id = document_id
+        .filter(|id| is_document_id(id))
+        .ok_or_else(|| format!("Could not find a document ID in {}", input))?;
+
+    Ok(DocumentReference {
+        document_id,
+        tab_id: query_value(&url, "tab"),
+        heading_id: url
+            .fragment()
+            .and_then(|fragment| {
+                fragment
+                    .split('&')
+                    .find_map(|pair| pair.strip_prefix("heading="))
+            })
+            .filter(|heading| !heading.is_empty())
+            .map(str::to_string),
+        format: ResponseFormat::default(),
+    })
+}
+
+/// The segment following the first `d` segment
+fn id_after_d(segments: &[&str]) -> Option<String> {
+    segments
+        .iter()
+        .position(|segment| *segment == "d")
+        .and_then(|position| segments.get(position + 1))
+        .map(|id| id.to_string())
+}
+
+/// A non-empty query parameter
+fn query_value(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bare_ids() {
+        // Given: A bare document ID with surrounding whitespace
+        // When: Parsing it
+        let reference = DocumentReference::parse("  1AbC-d_Ef  ").unwrap();
+
+        // Then: The trimmed ID should be returned without tab or heading
+        assert_eq!(reference.document_id, "1AbC-d_Ef");
+        assert_eq!(reference.tab_id, None);
+        assert_eq!(reference.heading_id, None);
+    }
+
+    #[test]
+    fn parses_docs_urls_with_tab_and_heading() {
+        // Given: An editor URL pointing at a heading in a tab
+        let url = "https://docs.google.com/document/d/1AbC-d_Ef/edit?tab=t.abc#heading=h.xyz";
+
+        // When: Parsing it
+        let reference = DocumentReference::parse(url).unwrap();
+
+        // Then: The ID, tab and heading should be extracted
+        assert_eq!(reference.document_id, "1AbC-d_Ef");
+        assert_eq!(reference.tab_id.as_deref(), Some("t.abc"));
+        assert_eq!(reference.heading_id.as_deref(), Some("h.xyz"));
+    }
+
+    #[test]
+    fn parses_workspace_drive_and_resource_links() {
+        // Given: Workspace, multi-account, scheme-less, Drive and resource links
+        let links = [
+            "https://docs.google.com/a/example.com/document/d/doc1/edit",
+            "https://docs.google.com/document/u/1/d/doc1/",
+            "docs.google.com/document/d/doc1",
+            "https://drive.google.com/file/d/doc1/view",
+            "https://drive.google.com/open?id=doc1",
+            "gdocs://document/doc1",
+        ];
+
+        // When: Parsing them
+        // Then: Each should name the same document
+        for link in links {
+            assert_eq!(
+                DocumentReference::parse(link).unwrap().document_id,
+                "doc1",
+                "{}",
+                link
+            );
+        }
+    }
+
+    #[test]
+    fn parses_resource_uris_with_tab_and_format() {
+        // Given: URIs for the raw JSON of a tab and the markdown of a document
+        // When: Parsing them
+        let json =
+            DocumentReference::parse("gdocs://document/abc_123/tab/t.0?format=json").unwrap();
+        let markdown =
+            DocumentReference::parse("gdocs://document/abc_123?format=markdown").unwrap();
+
+        // Then: The document, tab and format should be extracted
+        assert_eq!(json.document_id, "abc_123");
+        assert_eq!(json.tab_id.as_deref(), Some("t.0"));
+        assert_eq!(json.format, ResponseFormat::Json);
+        assert_eq!(markdown.tab_id, None);
+        assert_eq!(markdown.format, ResponseFormat::Markdown);
+    }
+
+    #[test]
+    fn rejects_unknown_resource_paths_and_queries() {
+        // Given: URIs that are not document resources
+        // When: Parsing them
+        // Then: They should be rejected
+        assert!(DocumentReference::parse("gdocs://folder/abc").is_err());
+        assert!(DocumentReference::parse("gdocs://document/abc/comments").is_err());
+        assert!(DocumentReference::parse("gdocs://document/?format=json").is_err());
+        assert!(
+            DocumentReference::parse("gdocs://document/abc?format=pdf")
+                .unwrap_err()
+                .contains("format=markdown or format=json")
+        );
+    }
+
+    #[test]
+    fn rejects_other_editors_and_hosts() {
+        // Given: Links to Sheets, Slides and another site, and a malformed ID
+        // When: Parsing them
+        let sheets = DocumentReference::parse("https://docs.google.com/spreadsheets/d/abc/edit")
+            .unwrap_err();
+        let slides = DocumentReference::parse("https://docs.google.com/presentation/d/abc/edit")
+            .unwrap_err();
+
+        // Then: Sheets and Slides links should be named as such, the rest rejected
+        assert!(sheets.contains("Google Sheets"));
+        assert!(slides.contains("Google Slides"));
+        assert!(DocumentReference::parse("https://example.com/document/d/abc").is_err());
+        assert!(DocumentReference::parse("abc def").is_err());
+        assert_eq!(
+            DocumentReference::parse(" ").unwrap_err(),
+            "Document ID cannot be empty"
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use super::GoogleDocsMcpServer;
+use super::documents::structured_result;
+use crate::models::{
+    Comment, CommentOutput, CommentReply, CommentReplyOutput, CommentsOutput, DocumentReference,
+    PostReplyOutput, ResponseFormat,
+};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Input for listing the comments on a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListCommentsParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// Include resolved comments (default: true)
+    #[serde(default = "default_include_resolved")]
+    pub include_resolved: bool,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+fn default_include_resolved() -> bool {
+    true
+}
+
+/// Input for adding a comment to a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct AddCommentParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// Text of the comment
+    pub content: String,
+
+    /// Document text the comment refers to, shown as its quote
+    #[serde(default)]
+    pub quoted_text: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for replying to a comment
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReplyToCommentParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// The comment ID, as returned by google_docs_list_comments
+    pub comment_id: String,
+
+    /// Text of the reply
+    pub content: String,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for resolving a comment
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ResolveCommentParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// The comment ID, as returned by google_docs_list_comments
+    pub comment_id: String,
+
+    /// Optional note posted with the resolution
+    #[serde(default)]
+    pub content: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+#[tool_router(router = comment_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// List the comments on a Google Document.
+    #[tool(
+        description = "List the comments on a Google Document with their IDs, quoted anchor text, author, resolved state and replies.",
+        output_schema = cached_schema_for_type::<CommentsOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_list_comments(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ListCommentsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        match client.drive().list_comments(&document_id).await {
+            Ok(listing) => {
+                let mut comments = listing.items;
+                if !params.include_resolved {
+                    comments.retain(|comment| !comment.resolved);
+                }
+                let output = CommentsOutput {
+                    document_id,
+                    comments: comments.iter().map(comment_output).collect(),
+                    truncated: listing.truncated,
+                };
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => {
+                        let mut response = format!(
+                            "# Comments\n\n- **Document ID**: `{}`\n- **Count**: {}\n",
+                            output.document_id,
+                            comments.len()
+                        );
+                        if output.truncated {
+                            response.push('\n');
+                            response.push_str(COMMENTS_TRUNCATED);
+                            response.push('\n');
+                        }
+                        for comment in &comments {
+                            response.push('\n');
+                            response.push_str(&format_comment_markdown(comment));
+                        }
+                        response
+                    }
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to list comments: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Add a comment to a Google Document.
+    #[tool(
+        description = "Add a comment to a Google Document. `quoted_text` is shown as the text the comment refers to; the Drive API cannot anchor comments to a position in the document, so they appear as document-level comments.",
+        output_schema = cached_schema_for_type::<CommentOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = false)
+    )]
+    async fn google_docs_add_comment(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<AddCommentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if params.content.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Comment content cannot be empty",
+            )]));
+        }
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let quoted_text = params.quoted_text.as_deref().filter(|q| !q.is_empty());
+        match client
+            .drive()
+            .create_comment(&document_id, &params.content, quoted_text)
+            .await
+        {
+            Ok(comment) => {
+                let output = comment_output(&comment);
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => format!(
+                        "# Comment Added\n\n- **Document ID**: `{}`\n\n{}",
+                        document_id,
+                        format_comment_markdown(&comment)
+                    ),
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to add comment: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Reply to a comment on a Google Document.
+    #[tool(
+        description = "Reply to a comment on a Google Document.",
+        output_schema = cached_schema_for_type::<PostReplyOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = false)
+    )]
+    async fn google_docs_reply_to_comment(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ReplyToCommentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.content.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Reply content cannot be empty",
+            )]));
+        }
+        self.post_reply(
+            &extensions,
+            &params.document_id,
+            &params.comment_id,
+            Some(&params.content),
+            None,
+            params.profile.as_deref(),
+            &params.response_format,
+        )
+        .await
+    }
+
+    /// Resolve a comment on a Google Document.
+    #[tool(
+        description = "Resolve a comment on a Google Document, optionally posting a closing note as a reply.",
+        output_schema = cached_schema_for_type::<PostReplyOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = true)
+    )]
+    async fn google_docs_resolve_comment(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ResolveCommentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let content = params.content.as_deref().filter(|c| !c.trim().is_empty());
+        self.post_reply(
+            &extensions,
+            &params.document_id,
+            &params.comment_id,
+            content,
+            Some("resolve"),
+            params.profile.as_deref(),
+            &params.response_format,
+        )
+        .await
+    }
+}
+
+impl GoogleDocsMcpServer {
+    /// Post a reply to a comment, optionally changing its state
+    #[allow(clippy::too_many_arguments)]
+    async fn post_reply(
+        &self,
+        extensions: &Extensions,
+        document_id: &str,
+        comment_id: &str,
+        content: Option<&str>,
+        action: Option<&str>,
+        profile: Option<&str>,
+        response_format: &ResponseFormat,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if comment_id.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Comment ID cannot be empty",
+            )]));
+        }
+
+        let client = match self.client_for(extensions, profile) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        match client
+            .drive()
+            .create_reply(&document_id, comment_id, content, action)
+            .await
+        {
+            Ok(reply) => {
+                let heading = if action == Some("resolve") {
+                    "Comment Resolved"
+                } else {
+                    "Reply Added"
+                };
+                let output = PostReplyOutput {
+                    document_id,
+                    comment_id: comment_id.to_string(),
+                    reply: reply_output(&reply),
+                };
+                let response = match response_format {
+                    ResponseFormat::Markdown => format!(
+                        "# {}\n\n- **Document ID**: `{}`\n- **Comment ID**: `{}`\n- **Reply ID**: `{}`\n",
+                        heading, output.document_id, output.comment_id, output.reply.reply_id
+                    ),
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to update comment: {:?}",
+                e
+            ))])),
+        }
+    }
+}
+
+/// Note shown when a document has more comments than could be fetched
+pub(super) const COMMENTS_TRUNCATED: &str = "The document has more comments than could be fetched, and Drive lists them oldest \
+     first, so the newest comments are missing.";
+
+/// A comment's quoted text as it appears in the document's plain text
+fn quoted_text(comment: &Comment) -> Option<String> {
+    comment
+        .quoted_file_content
+        .as_ref()
+        .map(|quote| unescape_html(&quote.value))
+        .filter(|quote| !quote.trim().is_empty())
+}
+
+/// Undo the HTML escaping Drive applies to quoted content
+fn unescape_html(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// Insert a footnote marker after the first occurrence of each comment's
+/// quoted text and append the comments as footnotes.
+///
+/// Comments whose quote cannot be found are listed without a marker.
+pub(super) fn inline_comments(content: &str, comments: &[Comment]) -> String {
+    let mut markers: Vec<(usize, String)> = Vec::new();
+    let mut footnotes = Vec::with_capacity(comments.len());
+
+    for (i, comment) in comments.iter().enumerate() {
+        let label = format!("[^c{}]", i + 1);
+        let position = quoted_text(comment)
+            .and_then(|quote| content.find(&quote).map(|start| start + quote.len()));
+        if let Some(position) = position {
+            markers.push((position, label.clone()));
+        }
+        let mut footnote = format!(
+            "{}: **{}**{}: {}",
+            label,
+            comment
+                .author
+                .as_ref()
+                .map(|author| author.label())
+                .unwrap_or("unknown"),
+            if comment.resolved { " (resolved)" } else { "" },
+            comment.content
+        );
+        if position.is_none()
+            && let Some(quote) = quoted_text(comment)
+        {
+            footnote.push_str(&format!(" (on \"{}\")", quote));
+        }
+        for reply in comment.replies.iter().filter(|r| !r.content.is_empty()) {
+            footnote.push_str(&format!(
+                " / **{}**: {}",
+                reply
+                    .author
+                    .as_ref()
+                    .map(|author| author.label())
+                    .unwrap_or("unknown"),
+                reply.content
+            ));
+        }
+        footnotes.push(footnote);
+    }
+
+    // Insert from the end so earlier positions stay valid
+    markers.sort_by_key(|(position, _)| std::cmp::Reverse(*position));
+    let mut output = content.to_string();
+    for (position, label) in markers {
+        output.insert_str(position, &label);
+    }
+
+    if !footnotes.is_empty() {
+        output.push_str("\n\n## Comments\n\n");
+        output.push_str(&footnotes.join("\n"));
+        output.push('\n');
+    }
+    output
+}
+
+/// Format one comment and its replies as markdown
+fn format_comment_markdown(comment: &Comment) -> String {
+    let author = comment
+        .author
+        .as_ref()
+        .map(|author| author.label())
+        .unwrap_or("unknown");
+    let mut lines = vec![
+        format!(
+            "## Comment `{}`{}",
+            comment.id,
+            if comment.resolved { " (resolved)" } else { "" }
+        ),
+        String::new(),
+        format!(
+            "- **Author**: {}{}",
+            author,
+            comment
+                .created_time
+                .as_deref()
+                .map(|t| format!(" ({})", t))
+                .unwrap_or_default()
+        ),
+    ];
+    if let Some(quote) = quoted_text(comment) {
+        lines.push(format!("- **Quoted text**: \"{}\"", quote));
+    }
+    lines.push(String::new());
+    lines.push(comment.content.clone());
+
+    if !comment.replies.is_empty() {
+        lines.push(String::new());
+        lines.push("### Replies".to_string());
+        lines.push(String::new());
+        for reply in &comment.replies {
+            let author = reply
+                .author
+                .as_ref()
+                .map(|author| author.label())
+                .unwrap_or("unknown");
+            let action = match reply.action.as_deref() {
+                Some("resolve") => " [resolved]",
+                Some("reopen") => " [reopened]",
+                _ => "",
+            };
+            lines.push(format!("- **{}**{}: {}", author, action, reply.content));
+        }
+    }
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Structured representation of a comment
+pub(super) fn comment_output(comment: &Comment) -> CommentOutput {
+    CommentOutput {
+        comment_id: comment.id.clone(),
+        content: comment.content.clone(),
+        author: comment.author.as_ref().map(|author| author.label().to_string()),
+        created_time: comment.created_time.clone(),
+        resolved: comment.resolved,
+        quoted_text: quoted_text(comment),
+        replies: comment.replies.iter().map(reply_output).collect(),
+    }
+}
+
+/// Structured representation of a reply
+fn reply_output(reply: &CommentReply) -> CommentReplyOutput {
+    CommentReplyOutput {
+        reply_id: reply.id.clone(),
+        content: reply.content.clone(),
+        author: reply.author.as_ref().map(|author| author.label().to_string()),
+        created_time: reply.created_time.clone(),
+        action: reply.action.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{DriveUser, QuotedFileContent};
+
+    fn comment(content: &str, quote: Option<&str>) -> Comment {
+        Comment {
+            id: "c".to_string(),
+            content: content.to_string(),
+            author: Some(DriveUser {
+                display_name: Some("Alice".to_string()),
+                email_address: None,
+            }),
+            quoted_file_content: quote.map(|value| QuotedFileContent {
+                mime_type: Some("text/html".to_string()),
+                value: value.to_string(),
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn comments_are_inlined_as_footnotes_after_their_quote() {
+        // Given: Two anchored comments, one with escaped HTML, and an unanchored one
+        let content = "Launch in Q3.\nBudget: R&D only.\n";
+        let comments = vec![
+            comment("Still Q3?", Some("Q3")),
+            comment("Clarify", Some("R&amp;D")),
+            comment("General note", None),
+        ];
+
+        // When: Inlining them
+        let output = inline_comments(content, &comments);
+
+        // Then: Markers follow the quoted text and every comment is a footnote
+        assert!(output.starts_with("Launch in Q3[^c1].\nBudget: R&D[^c2] only.\n"));
+        assert!(output.contains("[^c1]: **Alice**: Still Q3?"));
+        assert!(output.contains("[^c3]: **Alice**: General note"));
+    }
+}
@@ -0,0 +1,179 @@
+use super::GoogleDocsMcpServer;
+use crate::api::DriveSearch;
+use crate::models::{DocumentReference, Tab};
+use rmcp::{ErrorData as McpError, model::*};
+
+/// Drive documents searched when completing a document ID
+const SEARCHED_DRIVE_DOCUMENTS: u32 = 20;
+
+/// Prompt arguments and resource template variables holding a document ID
+const DOCUMENT_ID_ARGUMENTS: &[&str] = &["document_id", "documentId"];
+
+/// Prompt arguments and resource template variables holding a tab ID
+const TAB_ID_ARGUMENTS: &[&str] = &["tab_id", "tabId"];
+
+impl GoogleDocsMcpServer {
+    /// Suggest values for a prompt argument or resource template variable:
+    /// document IDs from recently fetched documents and Drive search, and tab
+    /// IDs from the tab tree of the document already chosen
+    pub(super) async fn complete_argument(
+        &self,
+        extensions: &Extensions,
+        request: CompleteRequestParam,
+    ) -> Result<CompleteResult, McpError> {
+        let name = request.argument.name.as_str();
+        let value = request.argument.value.trim();
+
+        let values = if DOCUMENT_ID_ARGUMENTS.contains(&name) {
+            self.complete_document_id(extensions, value).await?
+        } else if TAB_ID_ARGUMENTS.contains(&name) {
+            let document = request.context.as_ref().and_then(|context| {
+                DOCUMENT_ID_ARGUMENTS
+                    .iter()
+                    .find_map(|name| context.get_argument(name))
+            });
+            match document.map(|document| DocumentReference::parse(document)) {
+                Some(Ok(reference)) => {
+                    self.complete_tab_id(extensions, &reference.document_id, value)
+                        .await?
+                }
+                _ => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(CompleteResult {
+            completion: completion_info(values),
+        })
+    }
+
+    async fn complete_document_id(
+        &self,
+        extensions: &Extensions,
+        value: &str,
+    ) -> Result<Vec<String>, McpError> {
+        let client = self.client_for(extensions, None)?;
+        let search = DriveSearch {
+            name: Some(value.to_string()).filter(|name| !name.is_empty()),
+            page_size: SEARCHED_DRIVE_DOCUMENTS,
+            ..Default::default()
+        };
+
+        Ok(self
+            .known_documents(&client, search)
+            .await
+            .into_iter()
+            .filter(|(document_id, title)| matches(document_id, title, value))
+            .map(|(document_id, _)| document_id)
+            .collect())
+    }
+
+    async fn complete_tab_id(
+        &self,
+        extensions: &Extensions,
+        document_id: &str,
+        value: &str,
+    ) -> Result<Vec<String>, McpError> {
+        let client = self.client_for(extensions, None)?;
+
+        // A partly typed or mistyped document ID is expected while completing
+        let document = match client.get_document(document_id).await {
+            Ok(document) => document,
+            Err(e) => {
+                tracing::debug!(
+                    "No tab completions for document {}: {}",
+                    document_id,
+                    e.message
+                );
+                return Ok(Vec::new());
+            }
+        };
+
+        let mut tabs = Vec::new();
+        collect_tabs(&document.tabs, &mut tabs);
+        Ok(tabs
+            .into_iter()
+            .filter(|(tab_id, title)| matches(tab_id, title, value))
+            .map(|(tab_id, _)| tab_id)
+            .collect())
+    }
+}
+
+/// Whether `value` is a prefix of the ID or appears in the title, ignoring case
+fn matches(id: &str, title: &str, value: &str) -> bool {
+    id.starts_with(value) || title.to_lowercase().contains(&value.to_lowercase())
+}
+
+/// IDs and titles of tabs and their child tabs, depth first
+fn collect_tabs(tabs: &[Tab], out: &mut Vec<(String, String)>) {
+    for tab in tabs {
+        if let Some(properties) = &tab.tab_properties
+            && let Some(tab_id) = &properties.tab_id
+        {
+            let title = properties.title.clone().unwrap_or_default();
+            out.push((tab_id.clone(), title));
+        }
+        collect_tabs(&tab.child_tabs, out);
+    }
+}
+
+/// Completion values, capped at the number a response may carry
+fn completion_info(mut values: Vec<String>) -> CompletionInfo {
+    let total = values.len();
+    values.truncate(CompletionInfo::MAX_VALUES);
+    CompletionInfo {
+        values,
+        total: Some(total as u32),
+        has_more: Some(total > CompletionInfo::MAX_VALUES),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tab_completions_include_child_tabs_matched_by_title() {
+        // Given: A tab tree with a child tab
+        let tabs: Vec<Tab> = serde_json::from_value(serde_json::json!([
+            {
+                "tabProperties": { "tabId": "t.0", "title": "Overview" },
+                "childTabs": [{ "tabProperties": { "tabId": "t.1", "title": "Meeting notes" } }]
+            },
+            { "tabProperties": { "tabId": "t.2", "title": "Appendix" } }
+        ]))
+        .unwrap();
+
+        // When: Collecting the tabs and matching part of a title
+        let mut collected = Vec::new();
+        collect_tabs(&tabs, &mut collected);
+        let matched: Vec<&str> = collected
+            .iter()
+            .filter(|(tab_id, title)| matches(tab_id, title, "notes"))
+            .map(|(tab_id, _)| tab_id.as_str())
+            .collect();
+
+        // Then: Tabs should be listed depth first and the child tab matched
+        let ids: Vec<&str> = collected
+            .iter()
+            .map(|(tab_id, _)| tab_id.as_str())
+            .collect();
+        assert_eq!(ids, ["t.0", "t.1", "t.2"]);
+        assert_eq!(matched, ["t.1"]);
+    }
+
+    #[test]
+    fn completion_values_are_capped() {
+        // Given: More candidates than a response may carry
+        let values: Vec<String> = (0..150).map(|i| format!("doc-{}", i)).collect();
+
+        // When: Building the completion
+        let completion = completion_info(values);
+
+        // Then: The values should be truncated and the total reported
+        assert_eq!(completion.values.len(), CompletionInfo::MAX_VALUES);
+        assert_eq!(completion.total, Some(150));
+        assert_eq!(completion.has_more, Some(true));
+    }
+}
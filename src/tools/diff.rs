@@ -0,0 +1,404 @@
+use super::GoogleDocsMcpServer;
+use super::documents::{extract_text_content, structured_result};
+use super::drive::plain_file_name;
+use crate::api::GoogleDocsClient;
+use crate::config::{DRIVE_FEATURES_ENV, DriveFeature, EXPORT_DIR_ENV};
+use crate::models::{DiffOutput, DocumentReference, ExportFormat, ResponseFormat};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged paragraphs shown around each change by default
+const DEFAULT_CONTEXT_PARAGRAPHS: usize = 3;
+
+/// Input for comparing two versions of a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiffDocumentParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// Older revision to compare from (requires the "revisions" Drive feature)
+    #[serde(default)]
+    pub from_revision_id: Option<String>,
+
+    /// Earlier text of the document to compare from, e.g. from a previous get_document call
+    #[serde(default)]
+    pub snapshot_text: Option<String>,
+
+    /// Plain text snapshot in the export directory to compare from, e.g. a previous "txt" export
+    #[serde(default)]
+    pub snapshot_file: Option<String>,
+
+    /// Revision to compare to (defaults to the current document)
+    #[serde(default)]
+    pub to_revision_id: Option<String>,
+
+    /// Unchanged paragraphs shown around each change (default 3)
+    #[serde(default)]
+    pub context_paragraphs: Option<usize>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Version {
+    Revision(String),
+    SnapshotText(String),
+    SnapshotFile(String),
+    Current,
+}
+
+impl Version {
+    fn label(&self) -> String {
+        match self {
+            Version::Revision(id) => format!("revision {}", id),
+            Version::SnapshotText(_) => "snapshot".to_string(),
+            Version::SnapshotFile(name) => format!("snapshot {}", name),
+            Version::Current => "current".to_string(),
+        }
+    }
+}
+
+/// Paragraph-level differences between two texts
+#[derive(Debug)]
+struct ParagraphDiff {
+    unified: String,
+    added: usize,
+    removed: usize,
+}
+
+#[tool_router(router = diff_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// Compare two versions of a Google Document.
+    #[tool(
+        description = "Show what changed in a Google Document as a unified diff with one line per paragraph. Compare from a revision (`from_revision_id`) or an earlier snapshot (`snapshot_text`, or `snapshot_file` in the export directory) to another revision (`to_revision_id`) or, by default, the current document.",
+        output_schema = cached_schema_for_type::<DiffOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_diff(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<DiffDocumentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let (from, to) = match diff_versions(&params) {
+            Ok(versions) => versions,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let uses_revisions = [&from, &to]
+            .iter()
+            .any(|v| matches!(v, Version::Revision(_)));
+        if uses_revisions && !self.options.drive_enabled(DriveFeature::Revisions) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Comparing revisions requires the \"{}\" Drive feature (set {}={})",
+                DriveFeature::Revisions.name(),
+                DRIVE_FEATURES_ENV,
+                DriveFeature::Revisions.name()
+            ))]));
+        }
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let drive_export = export_current(&from, &to);
+        let old = match self
+            .version_text(&client, &document_id, &from, drive_export)
+            .await
+        {
+            Ok(text) => text,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let new = match self
+            .version_text(&client, &document_id, &to, drive_export)
+            .await
+        {
+            Ok(text) => text,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let diff = paragraph_diff(
+            &old,
+            &new,
+            &from.label(),
+            &to.label(),
+            params
+                .context_paragraphs
+                .unwrap_or(DEFAULT_CONTEXT_PARAGRAPHS),
+        );
+        let output = DiffOutput {
+            document_id,
+            from: from.label(),
+            to: to.label(),
+            paragraphs_added: diff.added,
+            paragraphs_removed: diff.removed,
+            diff: diff.unified,
+        };
+        let response = format_diff_response(&output, &params.response_format);
+        Ok(structured_result(response, &output))
+    }
+}
+
+impl GoogleDocsMcpServer {
+    /// Load the normalized plain text of one side of a comparison.
+    ///
+    /// With `drive_export`, the current document is read through the Drive
+    /// plain text export, so it renders lists, tables and other structure the
+    /// same way as a revision or an exported snapshot on the other side.
+    async fn version_text(
+        &self,
+        client: &GoogleDocsClient,
+        document_id: &str,
+        version: &Version,
+        drive_export: bool,
+    ) -> Result<String, String> {
+        let text = match version {
+            Version::Revision(revision_id) => {
+                let drive = client.drive();
+                let revision = drive
+                    .get_revision(document_id, revision_id)
+                    .await
+                    .map_err(|e| format!("Failed to get revision {}: {:?}", revision_id, e))?;
+                drive
+                    .revision_text(&revision)
+                    .await
+                    .map_err(|e| format!("Failed to get revision {}: {:?}", revision_id, e))?
+            }
+            Version::SnapshotText(text) => text.clone(),
+            Version::SnapshotFile(name) => {
+                let export_dir = self.options.export_dir.as_deref().ok_or_else(|| {
+                    format!(
+                        "snapshot_file requires an export directory (set {})",
+                        EXPORT_DIR_ENV
+                    )
+                })?;
+                let path = export_dir.join(plain_file_name(name)?);
+                tokio::fs::read_to_string(&path)
+                    .await
+                    .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?
+            }
+            Version::Current if drive_export => client
+                .drive()
+                .export(document_id, ExportFormat::Txt)
+                .await
+                .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                .map_err(|e| format!("Failed to export document: {:?}", e))?,
+            Version::Current => client
+                .get_document(document_id)
+                .await
+                .map(|document| extract_text_content(&document))
+                .map_err(|e| format!("Failed to get document: {:?}", e))?,
+        };
+        Ok(normalize_text(&text))
+    }
+}
+
+/// Whether the current document must be read through the Drive plain text
+/// export to match the other side: revisions are always read that way, and
+/// snapshot files are earlier "txt" exports. Snapshot text comes from
+/// get_document, so it is compared with the Docs API text instead.
+fn export_current(from: &Version, to: &Version) -> bool {
+    [from, to]
+        .iter()
+        .any(|v| matches!(v, Version::Revision(_) | Version::SnapshotFile(_)))
+}
+
+/// Work out which two versions to compare from the parameters
+fn diff_versions(params: &DiffDocumentParams) -> Result<(Version, Version), String> {
+    let non_empty = |value: &Option<String>| {
+        value
+            .as_deref()
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+
+    let mut sources = Vec::new();
+    if let Some(id) = non_empty(&params.from_revision_id) {
+        sources.push(Version::Revision(id));
+    }
+    if let Some(text) = params.snapshot_text.clone() {
+        sources.push(Version::SnapshotText(text));
+    }
+    if let Some(name) = non_empty(&params.snapshot_file) {
+        sources.push(Version::SnapshotFile(name));
+    }
+
+    let from = match sources.len() {
+        1 => sources.remove(0),
+        0 => {
+            return Err(
+                "Set one of from_revision_id, snapshot_text or snapshot_file to compare from"
+                    .to_string(),
+            );
+        }
+        _ => {
+            return Err(
+                "Set only one of from_revision_id, snapshot_text or snapshot_file".to_string(),
+            );
+        }
+    };
+    let to = match non_empty(&params.to_revision_id) {
+        Some(id) => Version::Revision(id),
+        None => Version::Current,
+    };
+    Ok((from, to))
+}
+
+/// Normalize text from the Docs API, Drive exports and snapshots so that only
+/// real content changes show up in a diff
+fn normalize_text(text: &str) -> String {
+    let text = text
+        .trim_start_matches('\u{feff}')
+        .replace("\r\n", "\n")
+        // Soft line breaks within a paragraph
+        .replace('\u{b}', "\n");
+    let mut lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let mut normalized = lines.join("\n");
+    normalized.push('\n');
+    normalized
+}
+
+/// Diff two texts paragraph by paragraph (each paragraph is one line)
+fn paragraph_diff(
+    old: &str,
+    new: &str,
+    old_label: &str,
+    new_label: &str,
+    context: usize,
+) -> ParagraphDiff {
+    let diff = TextDiff::from_lines(old, new);
+
+    let mut added = 0;
+    let mut removed = 0;
+    for change in diff.iter_all_changes() {
+        match change.tag() {
+            ChangeTag::Insert => added += 1,
+            ChangeTag::Delete => removed += 1,
+            ChangeTag::Equal => {}
+        }
+    }
+
+    let unified = diff
+        .unified_diff()
+        .context_radius(context)
+        .header(old_label, new_label)
+        .to_string();
+
+    ParagraphDiff {
+        unified,
+        added,
+        removed,
+    }
+}
+
+/// Format diff response
+fn format_diff_response(output: &DiffOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut response = format!(
+                "# Document Diff\n\n\
+                 - **Document ID**: `{}`\n\
+                 - **From**: {}\n\
+                 - **To**: {}\n\
+                 - **Paragraphs added**: {}\n\
+                 - **Paragraphs removed**: {}\n\n",
+                output.document_id,
+                output.from,
+                output.to,
+                output.paragraphs_added,
+                output.paragraphs_removed
+            );
+            if output.diff.is_empty() {
+                response.push_str("No differences.\n");
+            } else {
+                response.push_str(&format!("```diff\n{}```\n", output.diff));
+            }
+            response
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_and_api_text_normalize_identically() {
+        // Given: The same content as a Drive text export and as extracted API text
+        let exported = "\u{feff}Title\r\nFirst line\r\n\r\n";
+        let extracted = "Title\nFirst line\n";
+
+        // When: Normalizing both
+        // Then: They should compare equal
+        assert_eq!(normalize_text(exported), normalize_text(extracted));
+    }
+
+    #[test]
+    fn current_text_matches_the_rendering_of_the_other_side() {
+        // Given: The current document paired with each kind of earlier version
+        let revision = Version::Revision("12".to_string());
+        let file = Version::SnapshotFile("report.txt".to_string());
+        let text = Version::SnapshotText("Title\n".to_string());
+
+        // When: Deciding how to read the current document
+        // Then: Revisions and exported snapshot files should use the Drive export,
+        // and get_document snapshots the Docs API text
+        assert!(export_current(&revision, &Version::Current));
+        assert!(export_current(&file, &Version::Current));
+        assert!(!export_current(&text, &Version::Current));
+    }
+
+    #[test]
+    fn paragraph_diff_reports_changed_paragraphs() {
+        // Given: A document where one paragraph was reworded and one added
+        let old = "Intro\nLaunch in Q3.\nOutro\n";
+        let new = "Intro\nLaunch in Q4.\nOutro\nAppendix\n";
+
+        // When: Diffing them
+        let diff = paragraph_diff(old, new, "revision 1", "current", 1);
+
+        // Then: The unified diff should show whole paragraphs
+        assert_eq!(diff.added, 2);
+        assert_eq!(diff.removed, 1);
+        assert!(diff.unified.starts_with("--- revision 1\n+++ current\n"));
+        assert!(diff.unified.contains("-Launch in Q3.\n+Launch in Q4.\n"));
+    }
+
+    #[test]
+    fn diff_requires_exactly_one_source() {
+        // Given: Both a revision and a snapshot to compare from
+        let params: DiffDocumentParams = serde_json::from_value(serde_json::json!({
+            "document_id": "doc",
+            "from_revision_id": "12",
+            "snapshot_text": "old"
+        }))
+        .unwrap();
+
+        // When: Resolving the versions
+        let err = diff_versions(&params).unwrap_err();
+
+        // Then: The ambiguity should be rejected
+        assert!(err.contains("only one"));
+    }
+}
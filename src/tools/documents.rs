@@ -1,33 +1,119 @@
-use crate::api::GoogleDocsClient;
+use crate::api::{ClientProfiles, GoogleDocsClient};
+use crate::config::{DRIVE_FEATURES_ENV, DriveFeature, ServerOptions};
+use crate::http::client_identity;
+use super::comments::{COMMENTS_TRUNCATED, comment_output, inline_comments};
+use super::drive::DRIVE_TOOLS;
+use super::find::{build_matcher, regex_replace_requests};
+use super::paging::{
+    DEFAULT_PAGE_CHARS, document_headings, page_scope, read_page, select_sections, select_tab,
+};
+use super::progress::{CallProgress, cancelled};
+use super::subscriptions::Subscriptions;
 use crate::models::{
-    ContainsText, DeleteContentRangeRequest, Document, DocumentRequest,
-    GoogleDocsRequest, InsertTextRequest, Location, Range,
-    ReplaceAllTextRequest, ResponseFormat,
+    BatchUpdateResponse, Comment, ContainsText, CreateDocumentOutput, DeleteContentRangeRequest,
+    Document, DocumentOutput, DocumentReference, DocumentRequest, GoogleDocsRequest,
+    InsertTextRequest, Location, OperationReply, ParagraphOutput, Range, ReplaceAllTextRequest,
+    ResponseFormat, StructuralElement, Tab, TabOutput, TabsCriteria, UpdateDocumentOutput,
 };
 use rmcp::{
+    elicit_safe,
+    handler::server::common::cached_schema_for_type,
     handler::server::router::tool::ToolRouter,
-    handler::server::tool::Parameters,
+    service::{ElicitationError, RequestContext},
+    Peer, RoleServer,
+    handler::server::wrapper::Parameters,
     model::*,
     tool, tool_handler, tool_router,
     ErrorData as McpError,
 };
 use schemars::JsonSchema;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Google Docs MCP Server
 #[derive(Clone)]
 pub struct GoogleDocsMcpServer {
-    client: Arc<GoogleDocsClient>,
+    profiles: Arc<ClientProfiles>,
+    pub(super) options: Arc<ServerOptions>,
+    pub(super) subscriptions: Subscriptions,
+    /// Number of the MCP session, which keeps recent documents apart when
+    /// the client has no authenticated identity
+    session: u64,
     tool_router: ToolRouter<Self>,
 }
 
+/// Number of the next MCP session
+static NEXT_SESSION: AtomicU64 = AtomicU64::new(0);
+
+/// Tools that modify documents, which are not registered in read-only mode
+const WRITE_TOOLS: &[&str] = &[
+    "google_docs_create_document",
+    "google_docs_create_from_template",
+    "google_docs_update_document",
+    "google_docs_add_comment",
+    "google_docs_reply_to_comment",
+    "google_docs_resolve_comment",
+    "google_docs_share",
+    "google_docs_revoke_access",
+];
+
 /// Input for getting a Google Document
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetDocumentParams {
-    /// The document ID to retrieve
+    /// The document ID or URL to retrieve; a URL with a `tab` parameter selects that tab
     pub document_id: String,
 
+    /// Include comments as footnotes after the text they quote (requires the "comments" Drive feature)
+    #[serde(default)]
+    pub include_comments: bool,
+
+    /// Start the response with the document's outline of headings
+    #[serde(default)]
+    pub outline: bool,
+
+    /// Only return the sections under these headings (matched ignoring case), each running
+    /// to the next heading of the same or a higher level; an empty list is only allowed
+    /// with `outline`, and returns the outline alone
+    #[serde(default)]
+    pub sections: Option<Vec<String>>,
+
+    /// Read the document in pages of whole paragraphs totalling at most this many characters
+    #[serde(default)]
+    pub max_chars: Option<usize>,
+
+    /// `next_cursor` of the previous page, to read the next one with the same other
+    /// parameters; fails if the document changed since or the tab or sections differ
+    #[serde(default)]
+    pub cursor: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for creating a Google Document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateDocumentParams {
+    /// Title of the new document
+    pub title: String,
+
+    /// Plain text to seed the document body with
+    #[serde(default)]
+    pub content: Option<String>,
+
+    /// Drive folder to move the new document into (requires the "folders" Drive feature)
+    #[serde(default)]
+    pub folder_id: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
     /// Output format: "markdown" (default) or "json"
     #[serde(default)]
     pub response_format: ResponseFormat,
@@ -36,9 +122,14 @@ pub struct GetDocumentParams {
 /// Input for updating a Google Document
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateDocumentParams {
-    /// The document ID to update
+    /// The document ID or URL to update; a URL with a `tab` parameter is the default tab
+    /// for the operations
     pub document_id: String,
 
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
     /// List of update operations to apply
     pub requests: Vec<DocumentRequest>,
 
@@ -51,34 +142,318 @@ pub struct UpdateDocumentParams {
 impl GoogleDocsMcpServer {
     /// Create a new Google Docs MCP server
     pub fn new(client: GoogleDocsClient) -> Self {
+        Self::with_options(client, ServerOptions::default())
+    }
+
+    /// Create a new Google Docs MCP server with explicit options.
+    ///
+    /// The client's scopes are replaced with the ones the options call for, so
+    /// in read-only mode the access token itself cannot modify documents.
+    pub fn with_options(client: GoogleDocsClient, options: ServerOptions) -> Self {
+        Self::with_profiles(ClientProfiles::single(client), options)
+    }
+
+    /// Create a Google Docs MCP server serving several credential profiles.
+    ///
+    /// Every profile's client gets the scopes the options call for.
+    pub fn with_profiles(profiles: ClientProfiles, options: ServerOptions) -> Self {
+        let profiles = profiles.with_scopes(options.scopes());
+
+        let mut tool_router = Self::tool_router()
+            + Self::drive_tool_router()
+            + Self::comment_tool_router()
+            + Self::revision_tool_router()
+            + Self::diff_tool_router()
+            + Self::sharing_tool_router()
+            + Self::outline_tool_router()
+            + Self::find_tool_router();
+        if options.read_only {
+            for name in WRITE_TOOLS {
+                tool_router.remove_route(name);
+            }
+        }
+        for (feature, name) in DRIVE_TOOLS {
+            if !options.drive_enabled(*feature) {
+                tool_router.remove_route(name);
+            }
+        }
+
         Self {
-            client: Arc::new(client),
-            tool_router: Self::tool_router(),
+            profiles: Arc::new(profiles),
+            options: Arc::new(options),
+            subscriptions: Subscriptions::default(),
+            session: NEXT_SESSION.fetch_add(1, Ordering::Relaxed),
+            tool_router,
         }
     }
 
+    /// A copy of the server for a new MCP session, sharing its clients and
+    /// options but with no resource subscriptions or recent documents
+    pub fn for_session(&self) -> Self {
+        Self {
+            subscriptions: Subscriptions::default(),
+            session: NEXT_SESSION.fetch_add(1, Ordering::Relaxed),
+            ..self.clone()
+        }
+    }
+
+    /// Pick the API client for a tool call from the requested profile and
+    /// the authenticated HTTP client identity, if any. The documents it
+    /// fetches are remembered for that identity, or for the session when
+    /// there is none.
+    pub(super) fn client_for(
+        &self,
+        extensions: &Extensions,
+        profile: Option<&str>,
+    ) -> Result<Arc<GoogleDocsClient>, McpError> {
+        let identity = client_identity(extensions);
+        let client = self.profiles.resolve(
+            identity.as_ref().map(|identity| identity.0.as_str()),
+            profile,
+        )?;
+        let reader = match identity {
+            Some(identity) => format!("identity:{}", identity.0),
+            None => format!("session:{}", self.session),
+        };
+        Ok(Arc::new(client.for_reader(reader)))
+    }
+
     /// Get a Google Document by its ID.
-    #[tool(description = "Get a Google Document by its ID. Returns the document title and full text content from all tabs (including nested child tabs).")]
+    #[tool(
+        description = "Get a Google Document by its ID. Returns the document title and full text content from all tabs (including nested child tabs). For long documents, pass `outline` to see the headings and `sections` to read only some of them, or `max_chars` to read in pages by following `next_cursor`.",
+        output_schema = cached_schema_for_type::<DocumentOutput>(),
+        annotations(read_only_hint = true)
+    )]
     async fn google_docs_get_document(
         &self,
+        context: RequestContext<RoleServer>,
         Parameters(params): Parameters<GetDocumentParams>,
     ) -> Result<CallToolResult, McpError> {
-        if params.document_id.trim().is_empty() {
+        let DocumentReference {
+            document_id,
+            tab_id,
+            ..
+        } = match DocumentReference::parse(&params.document_id) {
+            Ok(reference) => reference,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        if params.sections.as_ref().is_some_and(Vec::is_empty) && !params.outline {
             return Ok(CallToolResult::error(vec![Content::text(
-                "Document ID cannot be empty",
+                "sections is empty; list the headings to read, or pass outline: true with an \
+                 empty list to get only the outline",
             )]));
         }
 
-        match self.client.get_document(&params.document_id).await {
-            Ok(document) => {
-                let response = format_get_response(&document, &params.response_format);
-                Ok(CallToolResult::success(vec![Content::text(response)]))
+        let client = match self.client_for(&context.extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        if params.include_comments && !self.options.drive_enabled(DriveFeature::Comments) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Including comments requires the \"{}\" Drive feature (set {}={})",
+                DriveFeature::Comments.name(),
+                DRIVE_FEATURES_ENV,
+                DriveFeature::Comments.name()
+            ))]));
+        }
+
+        let mut step = 0;
+        let progress = CallProgress::new(&context, 2 + u32::from(params.include_comments));
+
+        let comments = if params.include_comments {
+            progress.report(step, "Fetching comments").await;
+            let Some(result) = progress
+                .run(client.drive().list_comments(&document_id))
+                .await
+            else {
+                return Ok(cancelled());
+            };
+            step += 1;
+            match result {
+                Ok(listing) => Some(listing),
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Failed to get comments: {:?}",
+                        e
+                    ))]));
+                }
             }
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
-                "Failed to get document: {:?}",
-                e
-            ))])),
+        } else {
+            None
+        };
+
+        progress.report(step, "Fetching document").await;
+        let Some(result) = progress.run(client.get_document_json(&document_id)).await else {
+            return Ok(cancelled());
+        };
+        let raw = match result {
+            Ok(raw) => raw,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to get document: {:?}",
+                    e
+                ))]));
+            }
+        };
+
+        progress.report(step + 1, "Parsing document").await;
+        let mut document: Document = match serde_json::from_value(raw) {
+            Ok(document) => document,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to parse document: {}",
+                    e
+                ))]));
+            }
+        };
+
+        // A link to a tab narrows the document to that tab and its child tabs
+        if let Some(tab_id) = &tab_id
+            && let Err(e) = select_tab(&mut document, tab_id)
+        {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let outline = params.outline.then(|| document_headings(&document));
+        if let Some(sections) = &params.sections
+            && let Err(e) = select_sections(&mut document, sections)
+        {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
         }
+        let page = if params.max_chars.is_some() || params.cursor.is_some() {
+            let max_chars = params.max_chars.unwrap_or(DEFAULT_PAGE_CHARS);
+            let scope = page_scope(tab_id.as_deref(), params.sections.as_deref());
+            match read_page(&mut document, params.cursor.as_deref(), max_chars, scope) {
+                Ok(page) => Some(page),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            }
+        } else {
+            None
+        };
+
+        let comment_items = comments.as_ref().map(|listing| listing.items.as_slice());
+        let mut output = document_output(&document, comment_items);
+        output.comments_truncated = comments.as_ref().map(|listing| listing.truncated);
+        output.outline = outline;
+        output.page = page;
+        let response = format_document_output(&output, comment_items, &params.response_format);
+        progress.report(step + 2, "Done").await;
+        Ok(structured_result(response, &output))
+    }
+
+    /// Create a new Google Document.
+    #[tool(
+        description = "Create a new Google Document with a title, optionally seeded with plain text content and moved into a Drive folder. Returns the new document ID and URL. Without a folder, the document is owned by the service account and only visible to it until shared, so pass `folder_id` of a folder shared with the intended readers.",
+        output_schema = cached_schema_for_type::<CreateDocumentOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = false)
+    )]
+    async fn google_docs_create_document(
+        &self,
+        context: RequestContext<RoleServer>,
+        Parameters(params): Parameters<CreateDocumentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.title.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Title cannot be empty",
+            )]));
+        }
+
+        let folder_id = params
+            .folder_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty());
+        if folder_id.is_some() && !self.options.drive_enabled(DriveFeature::Folders) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Moving documents into folders requires the \"{}\" Drive feature \
+                 (set {}={})",
+                DriveFeature::Folders.name(),
+                DRIVE_FEATURES_ENV,
+                DriveFeature::Folders.name()
+            ))]));
+        }
+
+        let client = match self.client_for(&context.extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let content = params.content.as_deref().filter(|c| !c.is_empty());
+        let steps = 1 + u32::from(content.is_some()) + u32::from(folder_id.is_some());
+        let progress = CallProgress::new(&context, steps);
+
+        progress.report(0, "Creating document").await;
+        let Some(result) = progress.run(client.create_document(&params.title)).await else {
+            return Ok(cancelled());
+        };
+        let document = match result {
+            Ok(document) => document,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to create document: {:?}",
+                    e
+                ))]));
+            }
+        };
+
+        // The document exists from here on, so later failures must still
+        // report its ID to keep the caller from creating a duplicate
+        let mut done = 1;
+        if let Some(content) = content {
+            let seed = vec![GoogleDocsRequest {
+                insert_text: Some(InsertTextRequest {
+                    text: content.to_string(),
+                    location: Location {
+                        index: 1,
+                        tab_id: None,
+                        segment_id: None,
+                    },
+                }),
+                delete_content_range: None,
+                replace_all_text: None,
+            }];
+            progress.report(done, "Inserting content").await;
+            match progress
+                .run(client.batch_update(&document.document_id, seed, None))
+                .await
+            {
+                Some(Ok(_)) => done += 1,
+                Some(Err(e)) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Created document `{}` but failed to insert its content: {:?}",
+                        document.document_id, e
+                    ))]));
+                }
+                None => return Ok(cancelled_after_create(&document.document_id)),
+            }
+        }
+
+        if let Some(folder_id) = folder_id {
+            progress.report(done, "Moving document into folder").await;
+            match progress
+                .run(
+                    client
+                        .drive()
+                        .move_to_folder(&document.document_id, folder_id),
+                )
+                .await
+            {
+                Some(Ok(_)) => done += 1,
+                Some(Err(e)) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Created document `{}` but failed to move it into folder `{}`: {:?}",
+                        document.document_id, folder_id, e
+                    ))]));
+                }
+                None => return Ok(cancelled_after_create(&document.document_id)),
+            }
+        }
+        progress.report(done, "Done").await;
+
+        let response = format_create_response(&document, folder_id, &params.response_format);
+        Ok(structured_result(response, &create_output(&document, folder_id)))
     }
 
     /// Update a Google Document with various operations.
@@ -90,17 +465,29 @@ impl GoogleDocsMcpServer {
 Insert text at a specific position in the document.
 - `text` (string, required): The text to insert
 - `index` (integer, required): Position to insert at (1 = beginning of document body)
+- `tabId` (string, optional): Tab the index is in
 
 ### 2. deleteContentRange
 Delete content within a specified range.
 - `startIndex` (integer, required): Start position of the range to delete
 - `endIndex` (integer, required): End position of the range to delete
+- `tabId` (string, optional): Tab the range is in
 
 ### 3. replaceAllText
 Replace all occurrences of a text string.
 - `findText` (string, required): The text to search for
 - `replaceText` (string, required): The replacement text
 - `matchCase` (boolean, optional): Whether to match case (default: false)
+- `tabId` (string, optional): Only replace in this tab
+
+### 4. regexReplace
+Replace every match of a regular expression (Rust regex syntax), keeping the style of the matched text.
+- `pattern` (string, required): The regular expression to search for
+- `replacement` (string, required): The replacement text; `$1` or `${name}` insert a capture group, `$$` a literal `$`
+- `matchCase` (boolean, optional): Whether to match case (default: false)
+- `tabId` (string, optional): Only replace in this tab and its child tabs
+
+A regexReplace must be the only operation in its request. It is evaluated against the current document text, including table cells, headers, footers and footnotes, and fails if the document changes before it is applied or a match runs across a table boundary or other content that is not text.
 
 ## Example Request
 
@@ -133,17 +520,27 @@ Replace all occurrences of a text string.
 
 ## Notes
 - Index 1 is the beginning of the document body
+- When `document_id` is a URL with a `tab` parameter, operations without a `tabId` apply to that tab; otherwise index-based operations apply to the first tab and replacements to all tabs
 - To append text at the end, first get the document to find the last index
-- Operations are applied in order"#)]
+- Operations are applied in order
+- Use google_docs_find to preview what a regexReplace pattern matches"#,
+        output_schema = cached_schema_for_type::<UpdateDocumentOutput>(),
+        annotations(destructive_hint = true, idempotent_hint = false)
+    )]
     async fn google_docs_update_document(
         &self,
+        context: RequestContext<RoleServer>,
         Parameters(params): Parameters<UpdateDocumentParams>,
     ) -> Result<CallToolResult, McpError> {
-        if params.document_id.trim().is_empty() {
-            return Ok(CallToolResult::error(vec![Content::text(
-                "Document ID cannot be empty",
-            )]));
-        }
+        // Operations without a tab of their own apply to the tab the URL links to
+        let DocumentReference {
+            document_id,
+            tab_id: linked_tab,
+            ..
+        } = match DocumentReference::parse(&params.document_id) {
+            Ok(reference) => reference,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
 
         if params.requests.is_empty() {
             return Ok(CallToolResult::error(vec![Content::text(
@@ -151,23 +548,134 @@ Replace all occurrences of a text string.
             )]));
         }
 
-        // Convert user-friendly requests to Google Docs API format
-        let google_requests = match convert_requests(&params.requests) {
-            Ok(r) => r,
-            Err(e) => {
-                return Ok(CallToolResult::error(vec![Content::text(e)]));
-            }
+        // A regexReplace can only be resolved into requests once the document
+        // is fetched; everything else converts to Google Docs API format now
+        let (google_requests, regex_replace) = match params.requests.as_slice() {
+            [
+                DocumentRequest::RegexReplace {
+                    pattern,
+                    replacement,
+                    match_case,
+                    tab_id,
+                },
+            ] => match build_matcher(pattern, true, *match_case) {
+                Ok(matcher) => (
+                    Vec::new(),
+                    Some((
+                        matcher,
+                        replacement,
+                        tab_id.as_ref().or(linked_tab.as_ref()),
+                    )),
+                ),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            },
+            requests => match convert_requests(requests, linked_tab.as_deref()) {
+                Ok(r) => (r, None),
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(e)]));
+                }
+            },
         };
 
-        match self
-            .client
-            .batch_update(&params.document_id, google_requests)
-            .await
+        let risky = risky_operations(&params.requests, self.options.confirm_delete_chars);
+        if !risky.is_empty()
+            && let Err(e) = confirm_update(&context.peer, &document_id, &risky).await
         {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let client = match self.client_for(&context.extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let progress = CallProgress::new(&context, if regex_replace.is_some() { 2 } else { 1 });
+        let mut done = 0;
+        let mut required_revision_id = None;
+        let mut occurrences = None;
+        let google_requests = match regex_replace {
+            None => google_requests,
+            Some((matcher, replacement, tab_id)) => {
+                progress.report(done, "Fetching document").await;
+                let Some(document) = progress.run(client.get_document(&document_id)).await else {
+                    return Ok(cancelled());
+                };
+                let mut document = match document {
+                    Ok(document) => document,
+                    Err(e) => {
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "Failed to get document: {:?}",
+                            e
+                        ))]));
+                    }
+                };
+                if let Some(tab_id) = tab_id
+                    && let Err(e) = select_tab(&mut document, tab_id)
+                {
+                    return Ok(CallToolResult::error(vec![Content::text(e)]));
+                }
+                let (requests, count) =
+                    match regex_replace_requests(&document, &matcher, replacement) {
+                        Ok(replaced) => replaced,
+                        Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+                    };
+                if let Some(risk) = regex_replace_risk(
+                    matcher.as_str(),
+                    replacement,
+                    &requests,
+                    self.options.confirm_delete_chars,
+                ) && let Err(e) = confirm_update(&context.peer, &document_id, &[risk]).await
+                {
+                    return Ok(CallToolResult::error(vec![Content::text(e)]));
+                }
+                done += 1;
+                required_revision_id = document.revision_id;
+                occurrences = Some(count as i64);
+                requests
+            }
+        };
+
+        // All operations go out in one batchUpdate so they apply atomically.
+        // A regexReplace with no matches has nothing to send.
+        let result = if google_requests.is_empty() {
+            Ok(BatchUpdateResponse {
+                document_id: document_id.clone(),
+                replies: Vec::new(),
+            })
+        } else {
+            progress
+                .report(
+                    done,
+                    format!(
+                        "Applying batch 1 of 1 ({} operations)",
+                        occurrences.map_or(params.requests.len(), |count| count as usize)
+                    ),
+                )
+                .await;
+            let Some(result) = progress
+                .run(client.batch_update(
+                    &document_id,
+                    google_requests,
+                    required_revision_id.as_deref(),
+                ))
+                .await
+            else {
+                return Ok(cancelled());
+            };
+            result
+        };
+        progress.report(done + 1, "Done").await;
+
+        match result {
             Ok(result) => {
-                let response =
-                    format_update_response(&result.document_id, &params.requests, &params.response_format);
-                Ok(CallToolResult::success(vec![Content::text(response)]))
+                let mut output = update_output(&result, &params.requests);
+                if let Some(reply) = output.replies.first_mut()
+                    && occurrences.is_some()
+                {
+                    reply.occurrences_changed = occurrences;
+                }
+                let response = format_update_response(&output, &params.requests, &params.response_format);
+                Ok(structured_result(response, &output))
             }
             Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
                 "Failed to update document: {:?}",
@@ -180,22 +688,257 @@ Replace all occurrences of a text string.
 #[tool_handler]
 impl rmcp::ServerHandler for GoogleDocsMcpServer {
     fn get_info(&self) -> ServerInfo {
+        let mut instructions = if self.options.read_only {
+            "Google Docs MCP Server - Read Google Documents using Service Account authentication. \
+             This server is running in read-only mode: its access token only carries the \
+             documents.readonly scope and no tools that modify documents are available."
+                .to_string()
+        } else {
+            "Google Docs MCP Server - Read and update Google Documents using Service Account authentication"
+                .to_string()
+        };
+        if self.profiles.len() > 1 {
+            instructions.push_str(&format!(
+                "\n\nCredential profiles: {}. Pass `profile` to choose one (default: {}).",
+                self.profiles.names().collect::<Vec<_>>().join(", "),
+                self.profiles.default_profile()
+            ));
+        }
+        instructions.push_str(
+            "\n\nDocuments are also available as resources at gdocs://document/{documentId} \
+             and gdocs://document/{documentId}/tab/{tabId}; subscribe to one to be notified \
+             when the document changes.",
+        );
         ServerInfo {
-            instructions: Some(
-                "Google Docs MCP Server - Read and update Google Documents using Service Account authentication".into(),
-            ),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
+            instructions: Some(instructions),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .enable_resources_subscribe()
+                .enable_prompts()
+                .enable_completions()
+                .build(),
             ..Default::default()
         }
     }
+
+    async fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListPromptsResult, McpError> {
+        Ok(ListPromptsResult {
+            prompts: Self::document_prompts(),
+            next_cursor: None,
+        })
+    }
+
+    async fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<GetPromptResult, McpError> {
+        self.get_document_prompt(&context.extensions, &request.name, request.arguments)
+            .await
+    }
+
+    async fn complete(
+        &self,
+        request: CompleteRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CompleteResult, McpError> {
+        self.complete_argument(&context.extensions, request).await
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, McpError> {
+        Ok(ListResourcesResult {
+            resources: self.list_document_resources(&context.extensions).await?,
+            next_cursor: None,
+        })
+    }
+
+    async fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourceTemplatesResult, McpError> {
+        Ok(ListResourceTemplatesResult {
+            resource_templates: Self::document_resource_templates(),
+            next_cursor: None,
+        })
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, McpError> {
+        self.read_document_resource(&context.extensions, &request.uri)
+            .await
+    }
+
+    async fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.subscribe_document(&context.extensions, context.peer, &request.uri)
+            .await
+    }
+
+    async fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<(), McpError> {
+        self.unsubscribe_document(&request.uri);
+        Ok(())
+    }
+}
+
+/// Result of a create call cancelled after the document already exists
+fn cancelled_after_create(document_id: &str) -> CallToolResult {
+    CallToolResult::error(vec![Content::text(format!(
+        "Cancelled by the client after creating document `{}`",
+        document_id
+    ))])
 }
 
-/// Convert user-friendly requests to Google Docs API format
-fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsRequest>, String> {
+/// The user's answer when asked to confirm an update
+#[derive(Debug, Deserialize, JsonSchema)]
+struct UpdateConfirmation {
+    /// Apply the changes
+    confirm: bool,
+}
+
+elicit_safe!(UpdateConfirmation);
+
+/// Describe the operations that need the user's confirmation: deletions
+/// spanning more than `max_delete_chars` and replacements with empty text.
+/// A regexReplace is checked later, by `regex_replace_risk`.
+fn risky_operations(requests: &[DocumentRequest], max_delete_chars: usize) -> Vec<String> {
+    requests
+        .iter()
+        .filter_map(|req| match req {
+            DocumentRequest::DeleteContentRange {
+                start_index,
+                end_index,
+                ..
+            } => {
+                let span = usize::try_from(end_index - start_index).unwrap_or(0);
+                (span > max_delete_chars).then(|| {
+                    format!(
+                        "delete {} characters (indexes {} to {})",
+                        span, start_index, end_index
+                    )
+                })
+            }
+            DocumentRequest::ReplaceAllText {
+                find_text,
+                replace_text,
+                ..
+            } => replace_text.is_empty().then(|| {
+                format!(
+                    "remove every occurrence of \"{}\"",
+                    truncate_text(find_text, 50)
+                )
+            }),
+            // Checked by regex_replace_risk once the matches are known
+            DocumentRequest::RegexReplace { .. } | DocumentRequest::InsertText { .. } => None,
+        })
+        .collect()
+}
+
+/// Describe a regexReplace that needs the user's confirmation: one whose
+/// matches add up to more than `max_delete_chars`, or whose replacement is empty
+fn regex_replace_risk(
+    pattern: &str,
+    replacement: &str,
+    requests: &[GoogleDocsRequest],
+    max_delete_chars: usize,
+) -> Option<String> {
+    let ranges: Vec<&Range> = requests
+        .iter()
+        .filter_map(|req| req.delete_content_range.as_ref())
+        .map(|delete| &delete.range)
+        .collect();
+    let span: usize = ranges
+        .iter()
+        .map(|range| usize::try_from(range.end_index - range.start_index).unwrap_or(0))
+        .sum();
+    let pattern = truncate_text(pattern, 50);
+    if replacement.is_empty() && !ranges.is_empty() {
+        Some(format!(
+            "remove every match of /{}/, {} characters in all",
+            pattern, span
+        ))
+    } else if span > max_delete_chars {
+        Some(format!(
+            "replace {} characters across {} matches of /{}/",
+            span,
+            ranges.len(),
+            pattern
+        ))
+    } else {
+        None
+    }
+}
+
+/// Ask the user through MCP elicitation to confirm risky operations.
+///
+/// Clients without elicitation support cannot confirm, so the update is refused.
+async fn confirm_update(
+    peer: &Peer<RoleServer>,
+    document_id: &str,
+    risky: &[String],
+) -> Result<(), String> {
+    let changes = risky.join("; ");
+    if !peer.supports_elicitation() {
+        return Err(format!(
+            "This update would {}. Such changes need the user's confirmation, but the client \
+             does not support elicitation, so no changes were made.",
+            changes
+        ));
+    }
+
+    match peer
+        .elicit::<UpdateConfirmation>(format!(
+            "Allow this update to document {}? It will {}.",
+            document_id, changes
+        ))
+        .await
+    {
+        Ok(Some(UpdateConfirmation { confirm: true })) => Ok(()),
+        Ok(_)
+        | Err(ElicitationError::UserDeclined)
+        | Err(ElicitationError::UserCancelled)
+        | Err(ElicitationError::NoContent) => Err(format!(
+            "The user did not confirm that the update may {}, so no changes were made.",
+            changes
+        )),
+        Err(e) => Err(format!("Failed to ask the user for confirmation: {}", e)),
+    }
+}
+
+/// Convert user-friendly requests to Google Docs API format. Requests without
+/// a tab of their own apply to `default_tab` when given.
+fn convert_requests(
+    requests: &[DocumentRequest],
+    default_tab: Option<&str>,
+) -> Result<Vec<GoogleDocsRequest>, String> {
+    let tab = |tab_id: &Option<String>| tab_id.as_deref().or(default_tab).map(str::to_string);
     requests
         .iter()
         .map(|req| match req {
-            DocumentRequest::InsertText { text, index } => {
+            DocumentRequest::InsertText {
+                text,
+                index,
+                tab_id,
+            } => {
                 if *index < 1 {
                     return Err(
                         "Insert index must be at least 1 (1 = beginning of document body)"
@@ -205,7 +948,11 @@ fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsReques
                 Ok(GoogleDocsRequest {
                     insert_text: Some(InsertTextRequest {
                         text: text.clone(),
-                        location: Location { index: *index },
+                        location: Location {
+                            index: *index,
+                            tab_id: tab(tab_id),
+                            segment_id: None,
+                        },
                     }),
                     delete_content_range: None,
                     replace_all_text: None,
@@ -214,6 +961,7 @@ fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsReques
             DocumentRequest::DeleteContentRange {
                 start_index,
                 end_index,
+                tab_id,
             } => {
                 if *start_index < 1 {
                     return Err("Start index must be at least 1".to_string());
@@ -227,6 +975,8 @@ fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsReques
                         range: Range {
                             start_index: *start_index,
                             end_index: *end_index,
+                            tab_id: tab(tab_id),
+                            segment_id: None,
                         },
                     }),
                     replace_all_text: None,
@@ -236,6 +986,7 @@ fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsReques
                 find_text,
                 replace_text,
                 match_case,
+                tab_id,
             } => {
                 if find_text.is_empty() {
                     return Err("Find text cannot be empty".to_string());
@@ -249,9 +1000,16 @@ fn convert_requests(requests: &[DocumentRequest]) -> Result<Vec<GoogleDocsReques
                             match_case: *match_case,
                         },
                         replace_text: replace_text.clone(),
+                        tabs_criteria: tab(tab_id).map(|tab_id| TabsCriteria {
+                            tab_ids: vec![tab_id],
+                        }),
                     }),
                 })
             }
+            DocumentRequest::RegexReplace { .. } => Err(
+                "regexReplace must be the only operation in an update; send the other operations separately"
+                    .to_string(),
+            ),
         })
         .collect()
 }
@@ -262,10 +1020,10 @@ fn extract_text_from_body(body: &crate::models::DocumentBody) -> String {
     for element in &body.content {
         if let Some(ref paragraph) = element.paragraph {
             for para_element in &paragraph.elements {
-                if let Some(ref text_run) = para_element.text_run {
-                    if let Some(ref content) = text_run.content {
-                        text.push_str(content);
-                    }
+                if let Some(ref text_run) = para_element.text_run
+                    && let Some(ref content) = text_run.content
+                {
+                    text.push_str(content);
                 }
             }
         }
@@ -274,14 +1032,14 @@ fn extract_text_from_body(body: &crate::models::DocumentBody) -> String {
 }
 
 /// Extract plain text content from a tab (including nested child tabs)
-fn extract_text_from_tab(tab: &crate::models::Tab) -> String {
+pub(super) fn extract_text_from_tab(tab: &crate::models::Tab) -> String {
     let mut text = String::new();
 
     // Extract content from this tab's document_tab
-    if let Some(ref doc_tab) = tab.document_tab {
-        if let Some(ref body) = doc_tab.body {
-            text.push_str(&extract_text_from_body(body));
-        }
+    if let Some(ref doc_tab) = tab.document_tab
+        && let Some(ref body) = doc_tab.body
+    {
+        text.push_str(&extract_text_from_body(body));
     }
 
     // Recursively extract content from child tabs
@@ -293,7 +1051,7 @@ fn extract_text_from_tab(tab: &crate::models::Tab) -> String {
 }
 
 /// Extract plain text content from a document
-fn extract_text_content(document: &Document) -> String {
+pub(super) fn extract_text_content(document: &Document) -> String {
     // If tabs are present (includeTabsContent=true), extract from tabs
     if !document.tabs.is_empty() {
         let mut text = String::new();
@@ -312,40 +1070,71 @@ fn extract_text_content(document: &Document) -> String {
 }
 
 /// Format get document response
-fn format_get_response(document: &Document, format: &ResponseFormat) -> String {
-    let content = extract_text_content(document);
+pub(super) fn format_get_response(
+    document: &Document,
+    comments: Option<&[Comment]>,
+    format: &ResponseFormat,
+) -> String {
+    format_document_output(&document_output(document, comments), comments, format)
+}
 
+/// Format a document, with its outline and page when present
+fn format_document_output(
+    output: &DocumentOutput,
+    comments: Option<&[Comment]>,
+    format: &ResponseFormat,
+) -> String {
     match format {
         ResponseFormat::Markdown => {
-            let url = format!(
-                "https://docs.google.com/document/d/{}/edit",
-                document.document_id
-            );
-            format!(
+            let mut text = format!(
                 "# {}\n\n\
                  - **Document ID**: `{}`\n\
-                 - **URL**: [Open in Google Docs]({})\n\n\
-                 ## Content\n\n\
-                 {}",
-                document.title, document.document_id, url, content
-            )
-        }
-        ResponseFormat::Json => {
-            serde_json::json!({
-                "document_id": document.document_id,
-                "title": document.title,
-                "url": format!("https://docs.google.com/document/d/{}/edit", document.document_id),
-                "content": content,
-                "revision_id": document.revision_id
-            })
-            .to_string()
+                 - **URL**: [Open in Google Docs]({})\n\n",
+                output.title, output.document_id, output.url
+            );
+            if let Some(outline) = &output.outline {
+                text.push_str("## Outline\n\n");
+                for heading in outline {
+                    text.push_str(&format!(
+                        "{}- {}\n",
+                        "  ".repeat(usize::from(heading.level) - 1),
+                        heading.text
+                    ));
+                }
+                text.push('\n');
+            }
+            text.push_str("## Content\n\n");
+            match comments {
+                Some(comments) => text.push_str(&inline_comments(&output.content, comments)),
+                None => text.push_str(&output.content),
+            }
+            if output.comments_truncated == Some(true) {
+                text.push_str("\n\n");
+                text.push_str(COMMENTS_TRUNCATED);
+            }
+            if let Some(page) = &output.page {
+                text.push_str(&format!(
+                    "\n\n---\n\nParagraphs {}-{} of {}",
+                    page.first_paragraph + 1,
+                    page.first_paragraph + page.paragraphs,
+                    page.total_paragraphs
+                ));
+                match &page.next_cursor {
+                    Some(cursor) => {
+                        text.push_str(&format!(". Pass cursor `{}` for the next page.", cursor))
+                    }
+                    None => text.push_str(" (end of document)."),
+                }
+            }
+            text
         }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
     }
 }
 
 /// Format update document response
 fn format_update_response(
-    document_id: &str,
+    output: &UpdateDocumentOutput,
     requests: &[DocumentRequest],
     format: &ResponseFormat,
 ) -> String {
@@ -354,8 +1143,8 @@ fn format_update_response(
             let mut lines = vec![
                 "# Document Updated".to_string(),
                 String::new(),
-                format!("- **Document ID**: `{}`", document_id),
-                format!("- **Operations Applied**: {}", requests.len()),
+                format!("- **Document ID**: `{}`", output.document_id),
+                format!("- **Operations Applied**: {}", output.operations_count),
                 String::new(),
                 "## Operations".to_string(),
                 String::new(),
@@ -363,7 +1152,7 @@ fn format_update_response(
 
             for (i, req) in requests.iter().enumerate() {
                 let desc = match req {
-                    DocumentRequest::InsertText { text, index } => {
+                    DocumentRequest::InsertText { text, index, .. } => {
                         format!(
                             "{}. Inserted text at index {}: \"{}\"",
                             i + 1,
@@ -374,6 +1163,7 @@ fn format_update_response(
                     DocumentRequest::DeleteContentRange {
                         start_index,
                         end_index,
+                        ..
                     } => {
                         format!(
                             "{}. Deleted content from index {} to {}",
@@ -386,14 +1176,45 @@ fn format_update_response(
                         find_text,
                         replace_text,
                         match_case,
+                        ..
                     } => {
-                        format!(
+                        let mut desc = format!(
                             "{}. Replaced \"{}\" with \"{}\" (case-sensitive: {})",
                             i + 1,
                             truncate_text(find_text, 30),
                             truncate_text(replace_text, 30),
                             match_case
-                        )
+                        );
+                        if let Some(changed) = output
+                            .replies
+                            .get(i)
+                            .and_then(|reply| reply.occurrences_changed)
+                        {
+                            desc.push_str(&format!(", {} occurrences", changed));
+                        }
+                        desc
+                    }
+                    DocumentRequest::RegexReplace {
+                        pattern,
+                        replacement,
+                        match_case,
+                        ..
+                    } => {
+                        let mut desc = format!(
+                            "{}. Replaced matches of /{}/ with \"{}\" (case-sensitive: {})",
+                            i + 1,
+                            truncate_text(pattern, 30),
+                            truncate_text(replacement, 30),
+                            match_case
+                        );
+                        if let Some(changed) = output
+                            .replies
+                            .get(i)
+                            .and_then(|reply| reply.occurrences_changed)
+                        {
+                            desc.push_str(&format!(", {} occurrences", changed));
+                        }
+                        desc
                     }
                 };
                 lines.push(desc);
@@ -401,22 +1222,317 @@ fn format_update_response(
 
             lines.join("\n")
         }
-        ResponseFormat::Json => {
-            serde_json::json!({
-                "document_id": document_id,
-                "operations_count": requests.len(),
-                "success": true
-            })
-            .to_string()
-        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
     }
 }
 
-/// Truncate text for display
+/// Truncate text for display to at most `max_len` characters
 fn truncate_text(text: &str, max_len: usize) -> String {
-    if text.len() <= max_len {
-        text.to_string()
+    match text.char_indices().nth(max_len) {
+        Some((end, _)) => format!("{}...", &text[..end]),
+        None => text.to_string(),
+    }
+}
+
+/// Format create document response
+fn format_create_response(
+    document: &Document,
+    folder_id: Option<&str>,
+    format: &ResponseFormat,
+) -> String {
+    let url = format!(
+        "https://docs.google.com/document/d/{}/edit",
+        document.document_id
+    );
+
+    match format {
+        ResponseFormat::Markdown => {
+            let mut output = format!(
+                "# Document Created\n\n\
+                 - **Title**: {}\n\
+                 - **Document ID**: `{}`\n\
+                 - **URL**: [Open in Google Docs]({})\n",
+                document.title, document.document_id, url
+            );
+            if let Some(folder_id) = folder_id {
+                output.push_str(&format!("- **Folder ID**: `{}`\n", folder_id));
+            }
+            output
+        }
+        ResponseFormat::Json => serde_json::json!(create_output(document, folder_id)).to_string(),
+    }
+}
+
+/// A tool result carrying `output` as structured content, with `text` as the
+/// fallback for clients that do not read it
+pub(super) fn structured_result<T: Serialize>(text: String, output: &T) -> CallToolResult {
+    let mut result = CallToolResult::success(vec![Content::text(text)]);
+    result.structured_content = Some(serde_json::json!(output));
+    result
+}
+
+/// Structured representation of a document and, if fetched, its comments
+fn document_output(document: &Document, comments: Option<&[Comment]>) -> DocumentOutput {
+    let tabs = if document.tabs.is_empty() {
+        vec![TabOutput {
+            tab_id: None,
+            title: None,
+            index: None,
+            paragraphs: document.body.as_ref().map(|body| paragraph_outputs(&body.content)).unwrap_or_default(),
+            child_tabs: Vec::new(),
+        }]
     } else {
-        format!("{}...", &text[..max_len])
+        document.tabs.iter().map(tab_output).collect()
+    };
+
+    DocumentOutput {
+        document_id: document.document_id.clone(),
+        title: document.title.clone(),
+        url: format!(
+            "https://docs.google.com/document/d/{}/edit",
+            document.document_id
+        ),
+        revision_id: document.revision_id.clone(),
+        content: extract_text_content(document),
+        tabs,
+        comments: comments.map(|comments| comments.iter().map(comment_output).collect()),
+        comments_truncated: None,
+        outline: None,
+        page: None,
+    }
+}
+
+/// Structured representation of a tab and its child tabs
+fn tab_output(tab: &Tab) -> TabOutput {
+    let properties = tab.tab_properties.as_ref();
+    TabOutput {
+        tab_id: properties.and_then(|p| p.tab_id.clone()),
+        title: properties.and_then(|p| p.title.clone()),
+        index: properties.and_then(|p| p.index),
+        paragraphs: tab
+            .document_tab
+            .as_ref()
+            .and_then(|doc_tab| doc_tab.body.as_ref())
+            .map(|body| paragraph_outputs(&body.content))
+            .unwrap_or_default(),
+        child_tabs: tab.child_tabs.iter().map(tab_output).collect(),
+    }
+}
+
+/// The paragraphs among the structural elements of a body
+pub(super) fn paragraph_outputs(elements: &[StructuralElement]) -> Vec<ParagraphOutput> {
+    elements
+        .iter()
+        .filter_map(|element| {
+            let paragraph = element.paragraph.as_ref()?;
+            let text: String = paragraph
+                .elements
+                .iter()
+                .filter_map(|e| e.text_run.as_ref()?.content.as_deref())
+                .collect();
+            Some(ParagraphOutput {
+                start_index: element.start_index,
+                end_index: element.end_index,
+                style: paragraph
+                    .paragraph_style
+                    .as_ref()
+                    .and_then(|style| style.named_style_type.clone()),
+                text: text.strip_suffix('\n').unwrap_or(&text).to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Structured representation of a newly created document
+fn create_output(document: &Document, folder_id: Option<&str>) -> CreateDocumentOutput {
+    CreateDocumentOutput {
+        document_id: document.document_id.clone(),
+        title: document.title.clone(),
+        url: format!(
+            "https://docs.google.com/document/d/{}/edit",
+            document.document_id
+        ),
+        folder_id: folder_id.map(str::to_string),
+        revision_id: document.revision_id.clone(),
+    }
+}
+
+/// Structured representation of a batch update, pairing each operation with
+/// its reply
+fn update_output(result: &BatchUpdateResponse, requests: &[DocumentRequest]) -> UpdateDocumentOutput {
+    let replies = requests
+        .iter()
+        .enumerate()
+        .map(|(i, request)| {
+            let operation = match request {
+                DocumentRequest::InsertText { .. } => "insertText",
+                DocumentRequest::DeleteContentRange { .. } => "deleteContentRange",
+                DocumentRequest::ReplaceAllText { .. } => "replaceAllText",
+                DocumentRequest::RegexReplace { .. } => "regexReplace",
+            };
+            OperationReply {
+                operation: operation.to_string(),
+                occurrences_changed: result
+                    .replies
+                    .get(i)
+                    .and_then(|reply| reply["replaceAllText"]["occurrencesChanged"].as_i64()),
+            }
+        })
+        .collect();
+
+    UpdateDocumentOutput {
+        document_id: result.document_id.clone(),
+        operations_count: requests.len(),
+        success: true,
+        replies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_output_keeps_tabs_and_paragraph_styles() {
+        // Given: A document with a heading in a child tab
+        let document: Document = serde_json::from_value(serde_json::json!({
+            "documentId": "doc",
+            "title": "Plan",
+            "tabs": [{
+                "tabProperties": { "tabId": "t.0", "title": "Main", "index": 0 },
+                "childTabs": [{
+                    "tabProperties": { "tabId": "t.1", "title": "Notes", "index": 0 },
+                    "documentTab": { "body": { "content": [{
+                        "startIndex": 1,
+                        "endIndex": 9,
+                        "paragraph": {
+                            "elements": [{ "textRun": { "content": "Timeline\n" } }],
+                            "paragraphStyle": { "namedStyleType": "HEADING_1" }
+                        }
+                    }]}}
+                }]
+            }]
+        }))
+        .unwrap();
+
+        // When: Building the structured output
+        let output = document_output(&document, None);
+
+        // Then: The child tab's paragraph should carry its style and text
+        let paragraph = &output.tabs[0].child_tabs[0].paragraphs[0];
+        assert_eq!(output.tabs[0].child_tabs[0].tab_id.as_deref(), Some("t.1"));
+        assert_eq!(paragraph.style.as_deref(), Some("HEADING_1"));
+        assert_eq!(paragraph.text, "Timeline");
+        assert_eq!(output.content, "Timeline\n");
+    }
+
+    #[test]
+    fn large_deletions_and_empty_replacements_need_confirmation() {
+        // Given: A small deletion, a large one and an empty replacement
+        let requests: Vec<DocumentRequest> = serde_json::from_value(serde_json::json!([
+            { "deleteContentRange": { "startIndex": 1, "endIndex": 50 } },
+            { "deleteContentRange": { "startIndex": 100, "endIndex": 700 } },
+            { "replaceAllText": { "findText": "DRAFT", "replaceText": "" } }
+        ]))
+        .unwrap();
+
+        // When: Checking them against a 500 character limit
+        let risky = risky_operations(&requests, 500);
+
+        // Then: Only the large deletion and the replacement should need confirmation
+        assert_eq!(
+            risky,
+            [
+                "delete 600 characters (indexes 100 to 700)",
+                "remove every occurrence of \"DRAFT\""
+            ]
+        );
+    }
+
+    #[test]
+    fn regex_replacements_need_confirmation_by_total_deleted() {
+        // Given: Matches that delete 300 and 400 characters, and a smaller one
+        let requests: Vec<DocumentRequest> = serde_json::from_value(serde_json::json!([
+            { "deleteContentRange": { "startIndex": 600, "endIndex": 1000 } },
+            { "insertText": { "text": "x", "index": 600 } },
+            { "deleteContentRange": { "startIndex": 1, "endIndex": 301 } },
+            { "insertText": { "text": "x", "index": 1 } }
+        ]))
+        .unwrap();
+        let large = convert_requests(&requests, None).unwrap();
+        let small = convert_requests(&requests[2..], None).unwrap();
+
+        // When: Checking them against a 500 character limit
+        // Then: Only the total above the limit, or an empty replacement, should need confirmation
+        assert_eq!(
+            regex_replace_risk("(?s).*", "x", &large, 500).as_deref(),
+            Some("replace 700 characters across 2 matches of /(?s).*/")
+        );
+        assert_eq!(regex_replace_risk("(?s).*", "x", &small, 500), None);
+        assert_eq!(
+            regex_replace_risk("DRAFT", "", &small, 500).as_deref(),
+            Some("remove every match of /DRAFT/, 300 characters in all")
+        );
+        assert_eq!(regex_replace_risk("DRAFT", "", &[], 500), None);
+    }
+
+    #[test]
+    fn truncation_keeps_multibyte_characters_whole() {
+        // Given: Japanese text and an emoji, longer than the limit in bytes
+        let japanese = "議事録の下書き";
+        let emoji = "😀😀😀";
+
+        // When: Truncating them
+        // Then: They should be cut after whole characters
+        assert_eq!(truncate_text(japanese, 3), "議事録...");
+        assert_eq!(truncate_text(emoji, 3), "😀😀😀");
+        assert_eq!(truncate_text(emoji, 1), "😀...");
+    }
+
+    #[test]
+    fn operations_default_to_the_linked_tab() {
+        // Given: An insert without a tab and a deletion in another tab
+        let requests: Vec<DocumentRequest> = serde_json::from_value(serde_json::json!([
+            { "insertText": { "text": "Hi", "index": 1 } },
+            { "deleteContentRange": { "startIndex": 1, "endIndex": 3, "tabId": "t.other" } },
+            { "replaceAllText": { "findText": "Q3", "replaceText": "Q4" } }
+        ]))
+        .unwrap();
+
+        // When: Converting them for a URL linking to tab t.xyz
+        let converted = convert_requests(&requests, Some("t.xyz")).unwrap();
+
+        // Then: Only the operations without a tab should target the linked one
+        let json = serde_json::to_value(&converted).unwrap();
+        assert_eq!(json[0]["insertText"]["location"]["tabId"], "t.xyz");
+        assert_eq!(json[1]["deleteContentRange"]["range"]["tabId"], "t.other");
+        assert_eq!(
+            json[2]["replaceAllText"]["tabsCriteria"]["tabIds"],
+            serde_json::json!(["t.xyz"])
+        );
+    }
+
+    #[test]
+    fn update_output_pairs_replies_with_operations() {
+        // Given: An insert followed by a replace and the batchUpdate replies
+        let requests: Vec<DocumentRequest> = serde_json::from_value(serde_json::json!([
+            { "insertText": { "text": "Hi", "index": 1 } },
+            { "replaceAllText": { "findText": "Q3", "replaceText": "Q4" } }
+        ]))
+        .unwrap();
+        let result: BatchUpdateResponse = serde_json::from_value(serde_json::json!({
+            "documentId": "doc",
+            "replies": [{}, { "replaceAllText": { "occurrencesChanged": 3 } }]
+        }))
+        .unwrap();
+
+        // When: Building the structured output
+        let output = update_output(&result, &requests);
+
+        // Then: Each operation should have its reply
+        assert_eq!(output.replies[0].operation, "insertText");
+        assert_eq!(output.replies[0].occurrences_changed, None);
+        assert_eq!(output.replies[1].operation, "replaceAllText");
+        assert_eq!(output.replies[1].occurrences_changed, Some(3));
     }
 }
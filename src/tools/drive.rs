@@ -0,0 +1,831 @@
+use super::GoogleDocsMcpServer;
+use super::documents::structured_result;
+use super::progress::{CallProgress, cancelled};
+use crate::api::{DriveSearch, GoogleDocsClient};
+use crate::config::{DriveFeature, EXPORT_DIR_ENV};
+use crate::models::{
+    BatchUpdateResponse, ContainsText, DocumentReference, DriveFile, DriveFileList, ExportFormat,
+    ExportOutput, GoogleDocsRequest, ReplaceAllTextRequest, ResponseFormat, SearchOutput,
+    SearchResultOutput, TemplateOutput,
+};
+use base64::{Engine, engine::general_purpose::STANDARD};
+use chrono::{DateTime, NaiveDate, NaiveTime, SecondsFormat, Utc};
+use rmcp::{
+    ErrorData as McpError, RoleServer, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, service::RequestContext, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Tools backed by the Drive API, each registered only when its feature is enabled
+pub(super) const DRIVE_TOOLS: &[(DriveFeature, &str)] = &[
+    (DriveFeature::Search, "google_docs_search"),
+    (DriveFeature::Templates, "google_docs_create_from_template"),
+    (DriveFeature::Export, "google_docs_export"),
+    (DriveFeature::Comments, "google_docs_list_comments"),
+    (DriveFeature::Comments, "google_docs_add_comment"),
+    (DriveFeature::Comments, "google_docs_reply_to_comment"),
+    (DriveFeature::Comments, "google_docs_resolve_comment"),
+    (DriveFeature::Revisions, "google_docs_list_revisions"),
+    (DriveFeature::Revisions, "google_docs_get_revision"),
+    (DriveFeature::Sharing, "google_docs_list_permissions"),
+    (DriveFeature::Sharing, "google_docs_share"),
+    (DriveFeature::Sharing, "google_docs_revoke_access"),
+];
+
+/// Results per page when the caller does not ask for a page size
+const DEFAULT_PAGE_SIZE: u32 = 25;
+
+/// Largest page size the tool accepts
+const MAX_PAGE_SIZE: u32 = 100;
+
+/// Largest export returned inline in a tool result (1 MiB)
+const MAX_INLINE_EXPORT_BYTES: usize = 1024 * 1024;
+
+/// Input for searching Google Docs in Drive
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SearchDocumentsParams {
+    /// Text the document title must contain
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Words or phrases to search for in the document content and title
+    #[serde(default)]
+    pub query: Option<String>,
+
+    /// Email address of a document owner
+    #[serde(default)]
+    pub owner: Option<String>,
+
+    /// ID of the folder the documents are directly in
+    #[serde(default)]
+    pub folder_id: Option<String>,
+
+    /// Only documents modified at or after this time (RFC 3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub modified_after: Option<String>,
+
+    /// Only documents modified before this time (RFC 3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub modified_before: Option<String>,
+
+    /// Search this shared drive instead of the files the account can see
+    #[serde(default)]
+    pub shared_drive_id: Option<String>,
+
+    /// Maximum number of results (1-100, default 25)
+    #[serde(default)]
+    pub page_size: Option<u32>,
+
+    /// `next_page_token` from a previous search with the same criteria
+    #[serde(default)]
+    pub page_token: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for creating a document from a template
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CreateFromTemplateParams {
+    /// ID or URL of the template document to copy
+    pub template_id: String,
+
+    /// Title of the new document
+    pub title: String,
+
+    /// Folder to create the document in (defaults to the template's folder)
+    #[serde(default)]
+    pub folder_id: Option<String>,
+
+    /// Values keyed by placeholder name; `{"name": "Ada"}` replaces every `{{name}}`
+    #[serde(default)]
+    pub placeholders: BTreeMap<String, String>,
+
+    /// Whether placeholder names are matched case-sensitively (default: true)
+    #[serde(default = "default_match_case")]
+    pub match_case: bool,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+fn default_match_case() -> bool {
+    true
+}
+
+/// Where an exported file is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportDestination {
+    /// Write the file to the server's export directory
+    File,
+    /// Return the file in the tool result as an embedded resource
+    Inline,
+}
+
+/// Input for exporting a Google Document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ExportDocumentParams {
+    /// The document ID or URL to export
+    pub document_id: String,
+
+    /// Format to export to: "pdf", "docx", "odt", "html", "epub" or "txt"
+    pub format: ExportFormat,
+
+    /// "file" to write to the export directory, "inline" to return the content
+    /// (defaults to "file" when an export directory is configured)
+    #[serde(default)]
+    pub destination: Option<ExportDestination>,
+
+    /// File name to write (defaults to the document title); the extension is added if missing
+    #[serde(default)]
+    pub file_name: Option<String>,
+
+    /// Replace an existing file with the same name (default: false)
+    #[serde(default)]
+    pub overwrite: bool,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+#[tool_router(router = drive_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// Search Google Docs via the Drive API.
+    #[tool(
+        description = "Search the Google Docs this account can access by title, full text, owner, folder, modification time or shared drive. Returns titles, document IDs, URLs and modification times, most recently modified first (full-text searches are ranked by relevance). Pass `next_page_token` back as `page_token` to get more results.",
+        output_schema = cached_schema_for_type::<SearchOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_search(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<SearchDocumentsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let search = match build_search(&params) {
+            Ok(search) => search,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        match client.drive().search_documents(&search).await {
+            Ok(results) => {
+                let output = search_output(&results);
+                let response = format_search_response(&output, &params.response_format);
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to search documents: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Create a document by copying a template and filling its placeholders.
+    #[tool(
+        description = "Create a new Google Document by copying a template document and replacing `{{placeholder}}` markers with the given values. Returns the new document ID and URL and how many occurrences of each placeholder were replaced, so missing or misspelled placeholders are easy to spot.",
+        output_schema = cached_schema_for_type::<TemplateOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = false)
+    )]
+    async fn google_docs_create_from_template(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<CreateFromTemplateParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.template_id.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Template ID cannot be empty",
+            )]));
+        }
+        let template_id = match DocumentReference::parse_document_id(&params.template_id) {
+            Ok(template_id) => template_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if params.title.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Title cannot be empty",
+            )]));
+        }
+
+        let placeholders = match placeholder_markers(&params.placeholders) {
+            Ok(placeholders) => placeholders,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let folder_id = non_empty(&params.folder_id);
+        let copy = match client
+            .drive()
+            .copy_file(&template_id, &params.title, folder_id.as_deref())
+            .await
+        {
+            Ok(copy) => copy,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to copy template: {:?}",
+                    e
+                ))]));
+            }
+        };
+
+        let mut replacements = Vec::with_capacity(placeholders.len());
+        if !placeholders.is_empty() {
+            let requests = placeholders
+                .iter()
+                .map(|(marker, value)| GoogleDocsRequest {
+                    insert_text: None,
+                    delete_content_range: None,
+                    replace_all_text: Some(ReplaceAllTextRequest {
+                        contains_text: ContainsText {
+                            text: marker.clone(),
+                            match_case: params.match_case,
+                        },
+                        replace_text: value.clone(),
+                        tabs_criteria: None,
+                    }),
+                })
+                .collect();
+
+            // The copy exists from here on, so a failure must still report its ID
+            let result = match client.batch_update(&copy.id, requests, None).await {
+                Ok(result) => result,
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Created document `{}` from the template but failed to fill placeholders: {:?}",
+                        copy.id, e
+                    ))]));
+                }
+            };
+            for ((marker, _), count) in placeholders.iter().zip(occurrences_changed(&result)) {
+                replacements.push((marker.clone(), count));
+            }
+        }
+
+        let output = TemplateOutput {
+            document_id: copy.id.clone(),
+            title: copy.name.clone().unwrap_or_else(|| params.title.clone()),
+            url: document_url(&copy),
+            occurrences_changed: replacements.into_iter().collect(),
+        };
+        let response = format_template_response(&output, &params.response_format);
+        Ok(structured_result(response, &output))
+    }
+
+    /// Export a Google Document via the Drive API.
+    #[tool(
+        description = "Export a Google Document to PDF, DOCX, ODT, HTML, EPUB or plain text. With destination \"file\" the export is written to the server's export directory and its path returned; with \"inline\" the content is returned as an embedded resource (base64 for binary formats, up to 1 MiB).",
+        output_schema = cached_schema_for_type::<ExportOutput>(),
+        annotations(destructive_hint = false)
+    )]
+    async fn google_docs_export(
+        &self,
+        context: RequestContext<RoleServer>,
+        Parameters(params): Parameters<ExportDocumentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let export_dir = self.options.export_dir.as_deref();
+        let destination = params.destination.unwrap_or(if export_dir.is_some() {
+            ExportDestination::File
+        } else {
+            ExportDestination::Inline
+        });
+        if destination == ExportDestination::File && export_dir.is_none() {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Writing exports to files requires an export directory (set {}); \
+                 use destination \"inline\" instead",
+                EXPORT_DIR_ENV
+            ))]));
+        }
+        if let Some(ref name) = params.file_name
+            && let Err(e) = export_file_name(name, params.format)
+        {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let client = match self.client_for(&context.extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let progress = CallProgress::new(&context, 2);
+        progress
+            .report(
+                0,
+                format!("Exporting document as {}", params.format.extension()),
+            )
+            .await;
+        let Some(result) = progress
+            .run(client.drive().export(&document_id, params.format))
+            .await
+        else {
+            return Ok(cancelled());
+        };
+        let bytes = match result {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return Ok(CallToolResult::error(vec![Content::text(format!(
+                    "Failed to export document: {:?}",
+                    e
+                ))]));
+            }
+        };
+
+        let uri = format!(
+            "https://docs.google.com/document/d/{}/export?format={}",
+            document_id,
+            params.format.extension()
+        );
+
+        match (destination, export_dir) {
+            (ExportDestination::File, Some(export_dir)) => {
+                // Not cancellable, so a half-written file is never left behind
+                progress.report(1, "Writing export file").await;
+                let result = self
+                    .write_export(export_dir, &client, &document_id, &params, &bytes)
+                    .await;
+                progress.report(2, "Done").await;
+                match result {
+                    Ok(path) => {
+                        let output =
+                            export_output(&document_id, params.format, bytes.len(), Some(&path));
+                        let response = format_export_response(&output, &params.response_format);
+                        Ok(structured_result(response, &output))
+                    }
+                    Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+                }
+            }
+            _ => {
+                progress.report(2, "Done").await;
+                if bytes.len() > MAX_INLINE_EXPORT_BYTES {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "The export is {} bytes, more than the {} bytes that can be returned inline; \
+                         export to a file instead",
+                        bytes.len(),
+                        MAX_INLINE_EXPORT_BYTES
+                    ))]));
+                }
+
+                let size = bytes.len();
+                let mime_type = Some(params.format.mime_type().to_string());
+                let text = if params.format.is_text() {
+                    String::from_utf8(bytes).map_err(|e| e.into_bytes())
+                } else {
+                    Err(bytes)
+                };
+                let resource = match text {
+                    Ok(text) => ResourceContents::TextResourceContents {
+                        uri,
+                        mime_type,
+                        text,
+                        meta: None,
+                    },
+                    Err(bytes) => ResourceContents::BlobResourceContents {
+                        uri,
+                        mime_type,
+                        blob: STANDARD.encode(bytes),
+                        meta: None,
+                    },
+                };
+                let output = export_output(&document_id, params.format, size, None);
+                let response = format_export_response(&output, &params.response_format);
+                let mut result = structured_result(response, &output);
+                result.content.push(Content::resource(resource));
+                Ok(result)
+            }
+        }
+    }
+}
+
+impl GoogleDocsMcpServer {
+    /// Write an export into the export directory, returning the path written
+    async fn write_export(
+        &self,
+        export_dir: &Path,
+        client: &GoogleDocsClient,
+        document_id: &str,
+        params: &ExportDocumentParams,
+        bytes: &[u8],
+    ) -> Result<PathBuf, String> {
+        let stem = match non_empty(&params.file_name) {
+            Some(name) => name,
+            None => client
+                .drive()
+                .get_file(document_id, "id,name")
+                .await
+                .ok()
+                .and_then(|file| file.name)
+                .map(|title| sanitize_file_name(&title))
+                .unwrap_or_else(|| document_id.to_string()),
+        };
+        let file_name = export_file_name(&stem, params.format)?;
+        let path = export_dir.join(file_name);
+
+        let mut options = tokio::fs::OpenOptions::new();
+        options.write(true);
+        if params.overwrite {
+            options.create(true).truncate(true);
+        } else {
+            options.create_new(true);
+        }
+        let mut file = options.open(&path).await.map_err(|e| {
+            if e.kind() == std::io::ErrorKind::AlreadyExists {
+                format!(
+                    "{} already exists; pass overwrite: true or another file_name",
+                    path.display()
+                )
+            } else {
+                format!("Failed to create {}: {}", path.display(), e)
+            }
+        })?;
+        file.write_all(bytes)
+            .await
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        Ok(path)
+    }
+}
+
+/// Validate a requested export file name and add the format's extension if missing
+fn export_file_name(name: &str, format: ExportFormat) -> Result<String, String> {
+    let name = plain_file_name(name)?;
+    let extension = format!(".{}", format.extension());
+    if name.to_ascii_lowercase().ends_with(&extension) {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{}{}", name, extension))
+    }
+}
+
+/// Check that a file name has no directory components, so it stays inside the export directory
+pub(super) fn plain_file_name(name: &str) -> Result<&str, String> {
+    let name = name.trim();
+    if name.is_empty()
+        || name == "."
+        || name == ".."
+        || name.contains(['/', '\\'])
+        || name.chars().any(char::is_control)
+    {
+        return Err(format!(
+            "Invalid file name {:?}: it must be a plain file name without directories",
+            name
+        ));
+    }
+    Ok(name)
+}
+
+/// Turn a document title into a safe file name
+fn sanitize_file_name(title: &str) -> String {
+    let name: String = title
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || " -_.()".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(100)
+        .collect();
+    let name = name.trim().trim_start_matches('.').to_string();
+    if name.is_empty() {
+        "document".to_string()
+    } else {
+        name
+    }
+}
+
+/// Wrap placeholder names in `{{ }}`, accepting names that already include them
+fn placeholder_markers(
+    placeholders: &BTreeMap<String, String>,
+) -> Result<Vec<(String, String)>, String> {
+    placeholders
+        .iter()
+        .map(|(name, value)| {
+            let name = name.trim();
+            let name = name
+                .strip_prefix("{{")
+                .and_then(|n| n.strip_suffix("}}"))
+                .unwrap_or(name)
+                .trim();
+            if name.is_empty() {
+                return Err("Placeholder names cannot be empty".to_string());
+            }
+            Ok((format!("{{{{{}}}}}", name), value.clone()))
+        })
+        .collect()
+}
+
+/// The number of replacements made by each replaceAllText request, in request order
+fn occurrences_changed(result: &BatchUpdateResponse) -> Vec<i64> {
+    result
+        .replies
+        .iter()
+        .map(|reply| {
+            // Zero counts are omitted from the reply
+            reply
+                .get("replaceAllText")
+                .and_then(|r| r.get("occurrencesChanged"))
+                .and_then(|n| n.as_i64())
+                .unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Validate search parameters and convert them to Drive search criteria
+fn build_search(params: &SearchDocumentsParams) -> Result<DriveSearch, String> {
+    let page_size = params.page_size.unwrap_or(DEFAULT_PAGE_SIZE);
+    if !(1..=MAX_PAGE_SIZE).contains(&page_size) {
+        return Err(format!("page_size must be between 1 and {}", MAX_PAGE_SIZE));
+    }
+
+    Ok(DriveSearch {
+        name: non_empty(&params.name),
+        full_text: non_empty(&params.query),
+        owner: non_empty(&params.owner),
+        folder_id: non_empty(&params.folder_id),
+        modified_after: non_empty(&params.modified_after)
+            .map(|t| normalize_timestamp("modified_after", &t))
+            .transpose()?,
+        modified_before: non_empty(&params.modified_before)
+            .map(|t| normalize_timestamp("modified_before", &t))
+            .transpose()?,
+        shared_drive_id: non_empty(&params.shared_drive_id),
+        page_size,
+        page_token: non_empty(&params.page_token),
+    })
+}
+
+/// Trim an optional string, treating blank values as unset
+fn non_empty(value: &Option<String>) -> Option<String> {
+    value
+        .as_deref()
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// Convert an RFC 3339 timestamp or a date to the UTC form Drive queries expect
+fn normalize_timestamp(field: &str, value: &str) -> Result<String, String> {
+    parse_timestamp(field, value).map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Parse an RFC 3339 timestamp or a date (taken as midnight UTC)
+pub(super) fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc());
+    }
+    Err(format!(
+        "{} must be an RFC 3339 timestamp or a YYYY-MM-DD date, got {:?}",
+        field, value
+    ))
+}
+
+/// The browser URL of a document in search results
+fn document_url(file: &DriveFile) -> String {
+    file.web_view_link
+        .clone()
+        .unwrap_or_else(|| format!("https://docs.google.com/document/d/{}/edit", file.id))
+}
+
+/// Structured representation of a page of search results
+fn search_output(results: &DriveFileList) -> SearchOutput {
+    SearchOutput {
+        documents: results
+            .files
+            .iter()
+            .map(|file| SearchResultOutput {
+                document_id: file.id.clone(),
+                title: file.name.clone(),
+                url: document_url(file),
+                modified_time: file.modified_time.clone(),
+                owners: file
+                    .owners
+                    .iter()
+                    .filter_map(|o| o.email_address.clone())
+                    .collect(),
+            })
+            .collect(),
+        next_page_token: results.next_page_token.clone(),
+    }
+}
+
+/// Format search response
+fn format_search_response(output: &SearchOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut lines = vec![
+                "# Search Results".to_string(),
+                String::new(),
+                format!("Found {} document(s) on this page.", output.documents.len()),
+                String::new(),
+            ];
+
+            for document in &output.documents {
+                lines.push(format!(
+                    "- **{}**",
+                    document.title.as_deref().unwrap_or("(untitled)")
+                ));
+                lines.push(format!("  - **Document ID**: `{}`", document.document_id));
+                lines.push(format!(
+                    "  - **URL**: [Open in Google Docs]({})",
+                    document.url
+                ));
+                if let Some(ref modified) = document.modified_time {
+                    lines.push(format!("  - **Modified**: {}", modified));
+                }
+                if !document.owners.is_empty() {
+                    lines.push(format!("  - **Owners**: {}", document.owners.join(", ")));
+                }
+            }
+
+            if let Some(ref token) = output.next_page_token {
+                lines.push(String::new());
+                lines.push(format!(
+                    "More results are available. Pass `page_token`: `{}`",
+                    token
+                ));
+            }
+
+            lines.join("\n")
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+/// Structured representation of an export
+fn export_output(
+    document_id: &str,
+    format: ExportFormat,
+    size: usize,
+    path: Option<&Path>,
+) -> ExportOutput {
+    ExportOutput {
+        document_id: document_id.to_string(),
+        format,
+        mime_type: format.mime_type().to_string(),
+        size,
+        path: path.map(|p| p.display().to_string()),
+    }
+}
+
+/// Format export response
+fn format_export_response(output: &ExportOutput, response_format: &ResponseFormat) -> String {
+    match response_format {
+        ResponseFormat::Markdown => {
+            let mut lines = vec![
+                "# Document Exported".to_string(),
+                String::new(),
+                format!("- **Document ID**: `{}`", output.document_id),
+                format!("- **Format**: {}", output.format.extension()),
+                format!("- **Size**: {} bytes", output.size),
+            ];
+            match output.path {
+                Some(ref path) => lines.push(format!("- **File**: `{}`", path)),
+                None => lines.push("- **Content**: attached as an embedded resource".to_string()),
+            }
+            lines.join("\n")
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+/// Format create from template response
+fn format_template_response(output: &TemplateOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut lines = vec![
+                "# Document Created from Template".to_string(),
+                String::new(),
+                format!("- **Title**: {}", output.title),
+                format!("- **Document ID**: `{}`", output.document_id),
+                format!("- **URL**: [Open in Google Docs]({})", output.url),
+            ];
+            if !output.occurrences_changed.is_empty() {
+                lines.push(String::new());
+                lines.push("## Placeholders".to_string());
+                lines.push(String::new());
+                for (marker, count) in &output.occurrences_changed {
+                    let note = if *count == 0 { " (not found)" } else { "" };
+                    lines.push(format!("- `{}`: {} replaced{}", marker, count, note));
+                }
+            }
+            lines.join("\n")
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timestamps_are_normalized_to_utc() {
+        // Given: A timestamp with an offset and a plain date
+        // When: Normalizing them for a Drive query
+        let with_offset = normalize_timestamp("modified_after", "2024-05-01T12:00:00+02:00");
+        let date = normalize_timestamp("modified_after", "2024-05-01");
+        let invalid = normalize_timestamp("modified_after", "last week");
+
+        // Then: Both should become UTC RFC 3339 and anything else is rejected
+        assert_eq!(with_offset.unwrap(), "2024-05-01T10:00:00Z");
+        assert_eq!(date.unwrap(), "2024-05-01T00:00:00Z");
+        assert!(invalid.unwrap_err().contains("RFC 3339"));
+    }
+
+    #[test]
+    fn placeholder_names_are_wrapped_once() {
+        // Given: Placeholder names with and without braces
+        let placeholders = BTreeMap::from([
+            ("week".to_string(), "42".to_string()),
+            ("{{owner}}".to_string(), "Ada".to_string()),
+        ]);
+
+        // When: Building the markers
+        let markers = placeholder_markers(&placeholders).unwrap();
+
+        // Then: Each should be wrapped in exactly one pair of braces
+        assert_eq!(
+            markers,
+            vec![
+                ("{{week}}".to_string(), "42".to_string()),
+                ("{{owner}}".to_string(), "Ada".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn export_file_names_cannot_leave_the_export_directory() {
+        // Given: A plain name, a name with the extension and a path
+        // When: Validating them as PDF export names
+        let plain = export_file_name("Weekly report", ExportFormat::Pdf);
+        let with_extension = export_file_name("report.PDF", ExportFormat::Pdf);
+        let traversal = export_file_name("../secrets", ExportFormat::Pdf);
+
+        // Then: The extension is added once and paths are rejected
+        assert_eq!(plain.unwrap(), "Weekly report.pdf");
+        assert_eq!(with_extension.unwrap(), "report.PDF");
+        assert!(traversal.is_err());
+    }
+
+    #[test]
+    fn titles_are_sanitized_for_file_names() {
+        // Given: A title with path separators and a leading dot
+        // When: Sanitizing it
+        let name = sanitize_file_name(".RFC: a/b <draft>");
+
+        // Then: Unsafe characters are replaced
+        assert_eq!(name, "RFC_ a_b _draft_");
+    }
+
+    #[test]
+    fn missing_occurrence_counts_are_zero() {
+        // Given: Replies where the second replacement matched nothing
+        let result: BatchUpdateResponse = serde_json::from_str(
+            r#"{"documentId": "doc", "replies": [
+                {"replaceAllText": {"occurrencesChanged": 3}},
+                {"replaceAllText": {}}
+            ]}"#,
+        )
+        .unwrap();
+
+        // When: Reading the counts
+        let counts = occurrences_changed(&result);
+
+        // Then: The omitted count should be zero
+        assert_eq!(counts, vec![3, 0]);
+    }
+}
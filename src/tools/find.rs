@@ -0,0 +1,541 @@
+use super::GoogleDocsMcpServer;
+use super::documents::structured_result;
+use super::paging::{heading_level, paragraph_text, segments, select_tab};
+use crate::models::{
+    DeleteContentRangeRequest, Document, FindOutput, GoogleDocsRequest, InsertTextRequest,
+    Location, MatchOutput, Range, ResponseFormat, StructuralElement,
+};
+use regex::{Regex, RegexBuilder};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Characters of context shown on each side of a match by default
+const DEFAULT_CONTEXT_CHARS: usize = 40;
+
+/// Matches returned by default
+const DEFAULT_MAX_RESULTS: usize = 100;
+
+/// Input for finding text in a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct FindParams {
+    /// The document ID or URL; a URL with a `tab` parameter searches only that tab
+    pub document_id: String,
+
+    /// Text to search for, or a regular expression when `regex` is true
+    pub query: String,
+
+    /// Treat `query` as a regular expression (Rust regex syntax) instead of literal text
+    #[serde(default)]
+    pub regex: bool,
+
+    /// Match case (default: false)
+    #[serde(default)]
+    pub match_case: bool,
+
+    /// Only search this tab and its child tabs
+    #[serde(default)]
+    pub tab_id: Option<String>,
+
+    /// Characters of context to return on each side of a match (default: 40)
+    #[serde(default)]
+    pub context_chars: Option<usize>,
+
+    /// Maximum number of matches to return (default: 100)
+    #[serde(default)]
+    pub max_results: Option<usize>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// The text of a body or other segment, with the document index of every
+/// text run and the position of every heading. Table cells are included; a
+/// table of contents, whose text is generated from the headings, is not.
+pub(super) struct IndexedText {
+    pub(super) text: String,
+    /// Byte offset in `text` and document index of each text run
+    runs: Vec<(usize, Option<i32>)>,
+    /// Byte offset in `text` and text of each heading
+    headings: Vec<(usize, String)>,
+}
+
+impl IndexedText {
+    pub(super) fn new(content: &[StructuralElement]) -> Self {
+        let mut indexed = Self {
+            text: String::new(),
+            runs: Vec::new(),
+            headings: Vec::new(),
+        };
+        indexed.visit(content);
+        indexed
+    }
+
+    fn visit(&mut self, content: &[StructuralElement]) {
+        for element in content {
+            if let Some(paragraph) = &element.paragraph {
+                if heading_level(paragraph).is_some() {
+                    self.headings.push((
+                        self.text.len(),
+                        paragraph_text(paragraph).trim().to_string(),
+                    ));
+                }
+                for element in &paragraph.elements {
+                    if let Some(content) =
+                        element.text_run.as_ref().and_then(|r| r.content.as_deref())
+                    {
+                        self.runs.push((self.text.len(), element.start_index));
+                        self.text.push_str(content);
+                    }
+                }
+            }
+            if let Some(table) = &element.table {
+                for cell in table.table_rows.iter().flat_map(|row| &row.table_cells) {
+                    self.visit(&cell.content);
+                }
+            }
+        }
+    }
+
+    /// Document index range of the text between byte offsets `start` and
+    /// `end`, counted in UTF-16 code units like the Docs API. `None` when the
+    /// range crosses something that is not in the text, such as a table or
+    /// cell boundary, an image or a section break, or an index is unknown.
+    pub(super) fn range(&self, start: usize, end: usize) -> Option<(i32, i32)> {
+        let first = self
+            .runs
+            .partition_point(|(offset, _)| *offset <= start)
+            .checked_sub(1)?;
+        // The run holding the last character, not the one starting at `end`
+        let last = self
+            .runs
+            .partition_point(|(offset, _)| *offset < end)
+            .checked_sub(1)?;
+
+        let index_in = |run: usize, offset: usize| -> Option<i32> {
+            let (run_start, index) = self.runs[run];
+            Some(index? + self.text[run_start..offset].encode_utf16().count() as i32)
+        };
+        for run in first..last {
+            if index_in(run, self.runs[run + 1].0)? != self.runs[run + 1].1? {
+                return None;
+            }
+        }
+        Some((index_in(first, start)?, index_in(last, end)?))
+    }
+
+    /// Text of the closest heading at or above byte `offset`
+    fn heading_at(&self, offset: usize) -> Option<&str> {
+        let count = self.headings.partition_point(|(start, _)| *start <= offset);
+        self.headings[..count]
+            .last()
+            .map(|(_, heading)| heading.as_str())
+    }
+}
+
+/// Compile a search query, escaping it unless it is a regular expression
+pub(super) fn build_matcher(query: &str, regex: bool, match_case: bool) -> Result<Regex, String> {
+    let pattern = if regex {
+        query.to_string()
+    } else {
+        regex::escape(query)
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!match_case)
+        .build()
+        .map_err(|e| format!("Invalid regular expression: {}", e))
+}
+
+/// Find the non-empty matches of `matcher` in every segment of a document,
+/// returning at most `max_results` of them and the total count. Matches
+/// spanning something that is not text, such as a table boundary, cannot be
+/// edited by index and are left out.
+fn find_matches(
+    document: &Document,
+    matcher: &Regex,
+    context_chars: usize,
+    max_results: usize,
+) -> (Vec<MatchOutput>, usize) {
+    let mut matches = Vec::new();
+    let mut total = 0;
+    for (tab_id, segment_id, content) in segments(document) {
+        let indexed = IndexedText::new(content);
+        for found in matcher.find_iter(&indexed.text) {
+            if found.is_empty() {
+                continue;
+            }
+            let Some((start_index, end_index)) = indexed.range(found.start(), found.end()) else {
+                continue;
+            };
+            total += 1;
+            if matches.len() == max_results {
+                continue;
+            }
+
+            let before = &indexed.text[..found.start()];
+            let before_start = before
+                .char_indices()
+                .rev()
+                .nth(context_chars.saturating_sub(1))
+                .map_or(0, |(offset, _)| offset);
+            let after: String = indexed.text[found.end()..]
+                .chars()
+                .take(context_chars)
+                .collect();
+            matches.push(MatchOutput {
+                tab_id: tab_id.map(str::to_string),
+                segment_id: segment_id.map(str::to_string),
+                start_index,
+                end_index,
+                text: found.as_str().to_string(),
+                before: if context_chars == 0 {
+                    String::new()
+                } else {
+                    before[before_start..].to_string()
+                },
+                after,
+                heading: indexed.heading_at(found.start()).map(str::to_string),
+            });
+        }
+    }
+    (matches, total)
+}
+
+/// Delete and insert requests replacing every non-empty match of `matcher`
+/// with `replacement`, whose `$1` and `${name}` are expanded from the
+/// match's capture groups. Returns the requests and the number of matches.
+///
+/// Each segment's matches are replaced from last to first, so no request
+/// moves the indexes of the ones after it. A match spanning something that
+/// is not text, such as a table boundary, fails the whole replacement rather
+/// than deleting it.
+pub(super) fn regex_replace_requests(
+    document: &Document,
+    matcher: &Regex,
+    replacement: &str,
+) -> Result<(Vec<GoogleDocsRequest>, usize), String> {
+    let mut requests = Vec::new();
+    let mut count = 0;
+    for (tab_id, segment_id, content) in segments(document) {
+        let indexed = IndexedText::new(content);
+        let mut edits = Vec::new();
+        for captures in matcher.captures_iter(&indexed.text) {
+            let found = captures.get(0).expect("group 0 is the whole match");
+            if found.is_empty() {
+                continue;
+            }
+            let Some((start, end)) = indexed.range(found.start(), found.end()) else {
+                return Err(format!(
+                    "The match \"{}\" spans a table, image or other content that is not text, \
+                     so no changes were made; narrow the pattern so matches stay within text",
+                    found.as_str().chars().take(50).collect::<String>()
+                ));
+            };
+            let mut text = String::new();
+            captures.expand(replacement, &mut text);
+            edits.push((start, end, found.as_str().ends_with('\n'), text));
+        }
+
+        count += edits.len();
+        for (start, end, ends_paragraph, text) in edits.into_iter().rev() {
+            let delete = GoogleDocsRequest {
+                insert_text: None,
+                delete_content_range: Some(DeleteContentRangeRequest {
+                    range: Range {
+                        start_index: start,
+                        end_index: end,
+                        tab_id: tab_id.map(str::to_string),
+                        segment_id: segment_id.map(str::to_string),
+                    },
+                }),
+                replace_all_text: None,
+            };
+            if text.is_empty() {
+                requests.push(delete);
+                continue;
+            }
+
+            // Text inserted right after the match takes the style of its
+            // last character, so insert before deleting. A match ending a
+            // paragraph would put the text in the next one; replace it in
+            // place instead.
+            let insert = |index| GoogleDocsRequest {
+                insert_text: Some(InsertTextRequest {
+                    text: text.clone(),
+                    location: Location {
+                        index,
+                        tab_id: tab_id.map(str::to_string),
+                        segment_id: segment_id.map(str::to_string),
+                    },
+                }),
+                delete_content_range: None,
+                replace_all_text: None,
+            };
+            if ends_paragraph {
+                requests.extend([delete, insert(start)]);
+            } else {
+                requests.extend([insert(end), delete]);
+            }
+        }
+    }
+    Ok((requests, count))
+}
+
+#[tool_router(router = find_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// Find text in a Google Document.
+    #[tool(
+        description = "Find text in a Google Document, literally or with a regular expression, optionally case-sensitive and scoped to a tab. Table cells, headers, footers and footnotes are searched too. Returns every match with its start and end index, the text around it and the heading it falls under, ready for index-based edits with google_docs_update_document.",
+        output_schema = cached_schema_for_type::<FindOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_find(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<FindParams>,
+    ) -> Result<CallToolResult, McpError> {
+        if params.query.is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Query cannot be empty",
+            )]));
+        }
+        let matcher = match build_matcher(&params.query, params.regex, params.match_case) {
+            Ok(matcher) => matcher,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let mut document = match self
+            .fetch_document(&extensions, &params.document_id, params.profile.as_deref())
+            .await
+        {
+            Ok((document, _)) => document,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if let Some(tab_id) = params.tab_id.as_deref().map(str::trim)
+            && !tab_id.is_empty()
+            && let Err(e) = select_tab(&mut document, tab_id)
+        {
+            return Ok(CallToolResult::error(vec![Content::text(e)]));
+        }
+
+        let (matches, total_matches) = find_matches(
+            &document,
+            &matcher,
+            params.context_chars.unwrap_or(DEFAULT_CONTEXT_CHARS),
+            params.max_results.unwrap_or(DEFAULT_MAX_RESULTS),
+        );
+        let output = FindOutput {
+            document_id: document.document_id.clone(),
+            revision_id: document.revision_id.clone(),
+            total_matches,
+            matches,
+        };
+        let response = format_find_response(&output, &params.query, &params.response_format);
+        Ok(structured_result(response, &output))
+    }
+}
+
+/// Format find response
+fn format_find_response(output: &FindOutput, query: &str, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut text = format!(
+                "# Matches for \"{}\"\n\n- **Document ID**: `{}`\n- **Matches**: {}",
+                query, output.document_id, output.total_matches
+            );
+            if output.matches.len() < output.total_matches {
+                text.push_str(&format!(" (showing {})", output.matches.len()));
+            }
+            text.push_str("\n\n");
+
+            for (i, found) in output.matches.iter().enumerate() {
+                let mut location = format!(
+                    "{}. Indexes {}-{}",
+                    i + 1,
+                    found.start_index,
+                    found.end_index
+                );
+                if let Some(segment_id) = &found.segment_id {
+                    location.push_str(&format!(" in segment `{}`", segment_id));
+                }
+                if let Some(tab_id) = &found.tab_id {
+                    location.push_str(&format!(" in tab `{}`", tab_id));
+                }
+                if let Some(heading) = &found.heading {
+                    location.push_str(&format!(" under \"{}\"", heading));
+                }
+                text.push_str(&format!(
+                    "{}: …{}**{}**{}…\n",
+                    location,
+                    found.before.replace('\n', " "),
+                    found.text.replace('\n', " "),
+                    found.after.replace('\n', " ")
+                ));
+            }
+            text
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        serde_json::from_value(serde_json::json!({
+            "documentId": "doc1",
+            "title": "Doc",
+            "body": { "content": [
+                { "startIndex": 1, "endIndex": 7, "paragraph": {
+                    "elements": [{ "startIndex": 1, "textRun": { "content": "Intro\n" } }],
+                    "paragraphStyle": { "namedStyleType": "HEADING_1" }
+                } },
+                { "startIndex": 7, "endIndex": 24, "paragraph": {
+                    "elements": [
+                        { "startIndex": 7, "textRun": { "content": "😀 Hello " } },
+                        { "startIndex": 16, "textRun": { "content": "hello world\n" } }
+                    ]
+                } }
+            ] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn match_indexes_count_utf16_code_units() {
+        // Given: A paragraph starting with an emoji, two UTF-16 code units long
+        let document = document();
+        let matcher = build_matcher("hello", false, false).unwrap();
+
+        // When: Searching case-insensitively
+        let (matches, total) = find_matches(&document, &matcher, 3, 10);
+
+        // Then: Both matches should have API indexes and the heading above them
+        assert_eq!(total, 2);
+        let ranges: Vec<(i32, i32)> = matches
+            .iter()
+            .map(|found| (found.start_index, found.end_index))
+            .collect();
+        assert_eq!(ranges, [(10, 15), (16, 21)]);
+        assert_eq!(matches[0].before, "\n😀 ");
+        assert_eq!(matches[1].after, " wo");
+        assert_eq!(matches[1].heading.as_deref(), Some("Intro"));
+    }
+
+    #[test]
+    fn results_are_capped_but_counted() {
+        // Given: A case-sensitive regex with two matches
+        let document = document();
+        let matcher = build_matcher(r"[Hh]ello", true, true).unwrap();
+
+        // When: Returning at most one match
+        let (matches, total) = find_matches(&document, &matcher, 0, 1);
+
+        // Then: The total should still count both
+        assert_eq!(matches.len(), 1);
+        assert_eq!(total, 2);
+        assert!(matches[0].before.is_empty());
+    }
+
+    #[test]
+    fn regex_replacements_run_backwards_and_expand_groups() {
+        // Given: A pattern capturing the end of both spellings of "hello"
+        let document = document();
+        let matcher = build_matcher(r"h(ello)", true, false).unwrap();
+
+        // When: Computing the replacement requests
+        let (requests, count) = regex_replace_requests(&document, &matcher, "j$1").unwrap();
+
+        // Then: The later match should be replaced first, inserting after it
+        // and then deleting it
+        assert_eq!(count, 2);
+        let json = serde_json::to_value(&requests).unwrap();
+        assert_eq!(json[0]["insertText"]["text"], "jello");
+        assert_eq!(json[0]["insertText"]["location"]["index"], 21);
+        assert_eq!(json[1]["deleteContentRange"]["range"]["startIndex"], 16);
+        assert_eq!(json[1]["deleteContentRange"]["range"]["endIndex"], 21);
+        assert_eq!(json[2]["insertText"]["location"]["index"], 15);
+        assert_eq!(json[3]["deleteContentRange"]["range"]["startIndex"], 10);
+    }
+
+    /// A tab with a table between two paragraphs and a footer
+    fn document_with_table() -> Document {
+        let paragraph = |start: i32, text: &str| {
+            serde_json::json!({ "startIndex": start, "paragraph": {
+                "elements": [{ "startIndex": start, "textRun": { "content": text } }]
+            } })
+        };
+        serde_json::from_value(serde_json::json!({
+            "documentId": "doc1",
+            "title": "Doc",
+            "tabs": [{
+                "tabProperties": { "tabId": "t.0" },
+                "documentTab": {
+                    "body": { "content": [
+                        paragraph(1, "Before\n"),
+                        { "startIndex": 8, "endIndex": 17, "table": { "tableRows": [{
+                            "tableCells": [{ "content": [paragraph(11, "cell\n")] }]
+                        }] } },
+                        paragraph(17, "After\n")
+                    ] },
+                    "footers": { "kix.f1": { "content": [paragraph(0, "Page footer\n")] } }
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn table_cells_and_footers_are_searched() {
+        // Given: A document with text in a table cell and in a footer
+        let document = document_with_table();
+
+        // When: Searching for the cell text and the footer text
+        let cell = build_matcher("cell", false, false).unwrap();
+        let (cells, _) = find_matches(&document, &cell, 0, 10);
+        let footer = build_matcher("footer", false, false).unwrap();
+        let (footers, _) = find_matches(&document, &footer, 0, 10);
+
+        // Then: Both should be found with their indexes and segment
+        assert_eq!((cells[0].start_index, cells[0].end_index), (11, 15));
+        assert_eq!(cells[0].segment_id, None);
+        assert_eq!((footers[0].start_index, footers[0].end_index), (5, 11));
+        assert_eq!(footers[0].segment_id.as_deref(), Some("kix.f1"));
+    }
+
+    #[test]
+    fn matches_across_a_table_are_rejected() {
+        // Given: A pattern that would match from before a table to after it
+        let document = document_with_table();
+        let matcher = build_matcher(r"(?s)Before.*After", true, true).unwrap();
+
+        // When: Finding and replacing it
+        let (matches, total) = find_matches(&document, &matcher, 0, 10);
+        let err = regex_replace_requests(&document, &matcher, "x").unwrap_err();
+
+        // Then: Neither should produce a range that deletes the table
+        assert!(matches.is_empty());
+        assert_eq!(total, 0);
+        assert!(err.contains("spans a table"));
+    }
+
+    #[test]
+    fn literal_queries_are_escaped() {
+        // Given: A literal query containing regex metacharacters
+        // When: Building the matcher
+        let matcher = build_matcher("a.b", false, true).unwrap();
+
+        // Then: The dot should only match itself
+        assert!(matcher.is_match("a.b"));
+        assert!(!matcher.is_match("axb"));
+    }
+}
@@ -1,3 +1,16 @@
+mod comments;
+mod completions;
+mod diff;
 mod documents;
+mod drive;
+mod find;
+mod outline;
+mod paging;
+mod progress;
+mod prompts;
+mod resources;
+mod revisions;
+mod sharing;
+mod subscriptions;
 
 pub use documents::*;
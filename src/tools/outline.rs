@@ -0,0 +1,302 @@
+use super::GoogleDocsMcpServer;
+use super::documents::{paragraph_outputs, structured_result};
+use super::paging::{Section, document_headings, document_sections, paragraph_text, select_tab};
+use crate::models::{
+    Document, DocumentReference, HeadingOutput, OutlineOutput, ResponseFormat, SectionOutput,
+};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Input for getting a document's outline
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetOutlineParams {
+    /// The document ID or URL; a URL with a `tab` parameter lists only that tab's headings
+    pub document_id: String,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for getting one section of a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetSectionParams {
+    /// The document ID or URL; a URL with a `#heading=` anchor selects that heading
+    pub document_id: String,
+
+    /// Heading ID or heading text (matched ignoring case) of the section; may be omitted
+    /// when the URL has a `#heading=` anchor
+    #[serde(default)]
+    pub heading: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+#[tool_router(router = outline_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// Get the heading outline of a Google Document.
+    #[tool(
+        description = "Get the outline of a Google Document: every heading with its level, text, heading ID, tab and the index range of its section. Use it to find the part of a long document you need, then read it with google_docs_get_section.",
+        output_schema = cached_schema_for_type::<OutlineOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_get_outline(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<GetOutlineParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document = match self
+            .fetch_document(&extensions, &params.document_id, params.profile.as_deref())
+            .await
+        {
+            Ok((document, _)) => document,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let output = OutlineOutput {
+            document_id: document.document_id.clone(),
+            title: document.title.clone(),
+            revision_id: document.revision_id.clone(),
+            headings: document_headings(&document),
+        };
+        let response = format_outline_response(&output, &params.response_format);
+        Ok(structured_result(response, &output))
+    }
+
+    /// Get the content under one heading of a Google Document.
+    #[tool(
+        description = "Get one section of a Google Document: the heading and everything under it up to the next heading of the same or a higher level. Identify the heading by ID or text, or pass a URL with a #heading= anchor. Returns the text, paragraphs with their indexes, and the section's index range.",
+        output_schema = cached_schema_for_type::<SectionOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_get_section(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<GetSectionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let (document, heading_id) = match self
+            .fetch_document(&extensions, &params.document_id, params.profile.as_deref())
+            .await
+        {
+            Ok(fetched) => fetched,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let Some(heading) = params
+            .heading
+            .as_deref()
+            .map(str::trim)
+            .filter(|heading| !heading.is_empty())
+            .map(str::to_string)
+            .or(heading_id)
+        else {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Pass heading, or a document URL with a #heading= anchor",
+            )]));
+        };
+
+        let sections = document_sections(&document);
+        let section = match find_section(&sections, &heading) {
+            Ok(section) => section,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let output = SectionOutput {
+            document_id: document.document_id.clone(),
+            revision_id: document.revision_id.clone(),
+            heading: section.heading.clone(),
+            content: section
+                .elements
+                .iter()
+                .filter_map(|element| element.paragraph.as_ref())
+                .map(paragraph_text)
+                .collect(),
+            paragraphs: paragraph_outputs(section.elements),
+        };
+        let response = format_section_response(&output, &params.response_format);
+        Ok(structured_result(response, &output))
+    }
+}
+
+impl GoogleDocsMcpServer {
+    /// Fetch the document a reference names, narrowed to the tab it links
+    /// to, along with the heading it links to
+    pub(super) async fn fetch_document(
+        &self,
+        extensions: &Extensions,
+        document: &str,
+        profile: Option<&str>,
+    ) -> Result<(Document, Option<String>), String> {
+        let reference = DocumentReference::parse(document)?;
+        let client = self
+            .client_for(extensions, profile)
+            .map_err(|e| e.message.to_string())?;
+        let mut document = client
+            .get_document(&reference.document_id)
+            .await
+            .map_err(|e| format!("Failed to get document: {:?}", e))?;
+        if let Some(tab_id) = &reference.tab_id {
+            select_tab(&mut document, tab_id)?;
+        }
+        Ok((document, reference.heading_id))
+    }
+}
+
+/// The section whose heading has this ID or text, ignoring case
+fn find_section<'a, 'b>(
+    sections: &'a [Section<'b>],
+    heading: &str,
+) -> Result<&'a Section<'b>, String> {
+    let by_id: Vec<&Section> = sections
+        .iter()
+        .filter(|section| section.heading.heading_id.as_deref() == Some(heading))
+        .collect();
+    let matches = if by_id.is_empty() {
+        let text = heading.to_lowercase();
+        sections
+            .iter()
+            .filter(|section| section.heading.text.to_lowercase() == text)
+            .collect()
+    } else {
+        by_id
+    };
+
+    match matches.as_slice() {
+        [section] => Ok(section),
+        [] => Err(format!(
+            "No heading matches \"{}\"; use google_docs_get_outline to list the headings",
+            heading
+        )),
+        several => Err(format!(
+            "Several headings match \"{}\"; pass one of their heading IDs instead: {}",
+            heading,
+            several
+                .iter()
+                .map(|section| describe_heading(&section.heading))
+                .collect::<Vec<_>>()
+                .join(", ")
+        )),
+    }
+}
+
+/// Heading ID, tab and range of a heading, for listing
+fn describe_heading(heading: &HeadingOutput) -> String {
+    let mut description = format!("`{}`", heading.heading_id.as_deref().unwrap_or("no ID"));
+    if let Some(tab_id) = &heading.tab_id {
+        description.push_str(&format!(" in tab `{}`", tab_id));
+    }
+    if let (Some(start), Some(end)) = (heading.start_index, heading.end_index) {
+        description.push_str(&format!(", indexes {}-{}", start, end));
+    }
+    description
+}
+
+/// Format get outline response
+fn format_outline_response(output: &OutlineOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut text = format!(
+                "# Outline of {}\n\n- **Document ID**: `{}`\n\n",
+                output.title, output.document_id
+            );
+            if output.headings.is_empty() {
+                text.push_str("The document has no headings.\n");
+            }
+            for heading in &output.headings {
+                text.push_str(&format!(
+                    "{}- {} ({})\n",
+                    "  ".repeat(usize::from(heading.level) - 1),
+                    heading.text,
+                    describe_heading(heading)
+                ));
+            }
+            text
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+/// Format get section response
+fn format_section_response(output: &SectionOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => format!(
+            "# {}\n\n\
+             - **Document ID**: `{}`\n\
+             - **Heading**: {}\n\n\
+             ## Content\n\n\
+             {}",
+            output.heading.text,
+            output.document_id,
+            describe_heading(&output.heading),
+            output.content
+        ),
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document() -> Document {
+        serde_json::from_value(serde_json::json!({
+            "documentId": "doc1",
+            "title": "Doc",
+            "body": { "content": [
+                { "paragraph": {
+                    "elements": [{ "textRun": { "content": "Notes\n" } }],
+                    "paragraphStyle": { "namedStyleType": "HEADING_1", "headingId": "h.a" }
+                } },
+                { "paragraph": {
+                    "elements": [{ "textRun": { "content": "First\n" } }]
+                } },
+                { "paragraph": {
+                    "elements": [{ "textRun": { "content": "Notes\n" } }],
+                    "paragraphStyle": { "namedStyleType": "HEADING_1", "headingId": "h.b" }
+                } }
+            ] }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn finds_sections_by_heading_id() {
+        // Given: Two sections with the same heading text
+        let document = document();
+        let sections = document_sections(&document);
+
+        // When: Looking one up by its heading ID
+        let section = find_section(&sections, "h.a").unwrap();
+
+        // Then: Its heading and body paragraph should be returned
+        assert_eq!(section.heading.heading_id.as_deref(), Some("h.a"));
+        assert_eq!(section.elements.len(), 2);
+    }
+
+    #[test]
+    fn ambiguous_heading_text_lists_the_heading_ids() {
+        // Given: Two sections with the same heading text
+        let document = document();
+        let sections = document_sections(&document);
+
+        // When: Looking one up by its text
+        let err = find_section(&sections, "notes").map(|_| ()).unwrap_err();
+
+        // Then: Both heading IDs should be offered
+        assert!(err.contains("h.a") && err.contains("h.b"));
+    }
+}
@@ -0,0 +1,549 @@
+use super::resources::find_tab;
+use crate::models::{
+    Document, DocumentBody, HeadingOutput, PageOutput, Paragraph, StructuralElement, Tab,
+};
+use base64::{Engine, engine::general_purpose::URL_SAFE_NO_PAD};
+
+/// Characters per page when a cursor is given without `max_chars`
+pub(super) const DEFAULT_PAGE_CHARS: usize = 20_000;
+
+/// Where a document read in pages continues
+#[derive(Debug, PartialEq, Eq)]
+struct Cursor {
+    /// Revision the earlier pages were read from
+    revision_id: String,
+    /// [`page_scope`] of the tab and sections the earlier pages were read from
+    scope: u64,
+    /// Position of the next paragraph to return
+    paragraph: usize,
+}
+
+impl Cursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!(
+            "{}:{:x}:{}",
+            self.paragraph, self.scope, self.revision_id
+        ))
+    }
+
+    fn decode(cursor: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid cursor \"{}\"", cursor);
+        let bytes = URL_SAFE_NO_PAD
+            .decode(cursor.trim())
+            .map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = text.splitn(3, ':');
+        let (Some(paragraph), Some(scope), Some(revision_id)) =
+            (parts.next(), parts.next(), parts.next())
+        else {
+            return Err(invalid());
+        };
+        Ok(Self {
+            revision_id: revision_id.to_string(),
+            scope: u64::from_str_radix(scope, 16).map_err(|_| invalid())?,
+            paragraph: paragraph.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Fingerprint of the tab and sections a document is read from in pages, so
+/// a cursor cannot be reused with a different selection.
+///
+/// This is a 64-bit FNV-1a hash, which unlike the standard library's hasher
+/// is fixed, so cursors stay valid across server builds and restarts.
+pub(super) fn page_scope(tab_id: Option<&str>, sections: Option<&[String]>) -> u64 {
+    // NUL cannot appear in tab IDs or headings, so it keeps the parts apart
+    let mut key = match tab_id {
+        Some(tab_id) => format!("tab:{}", tab_id),
+        None => String::new(),
+    };
+    if let Some(sections) = sections {
+        key.push_str("\0sections");
+        for section in sections {
+            key.push('\0');
+            key.push_str(&section.trim().to_lowercase());
+        }
+    }
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Text of a paragraph, including its trailing newline
+pub(super) fn paragraph_text(paragraph: &Paragraph) -> String {
+    paragraph
+        .elements
+        .iter()
+        .filter_map(|e| e.text_run.as_ref()?.content.as_deref())
+        .collect()
+}
+
+/// Level of a HEADING_1 to HEADING_6 paragraph
+pub(super) fn heading_level(paragraph: &Paragraph) -> Option<u8> {
+    paragraph
+        .paragraph_style
+        .as_ref()?
+        .named_style_type
+        .as_deref()?
+        .strip_prefix("HEADING_")?
+        .parse()
+        .ok()
+        .filter(|level| (1..=6).contains(level))
+}
+
+/// Bodies of a document with the ID of their tab, in the order their text is
+/// rendered: each tab before its child tabs, or the body of a document
+/// without tabs
+pub(super) fn bodies(document: &Document) -> Vec<(Option<&str>, &DocumentBody)> {
+    fn visit<'a>(tabs: &'a [Tab], out: &mut Vec<(Option<&'a str>, &'a DocumentBody)>) {
+        for tab in tabs {
+            if let Some(body) = tab.document_tab.as_ref().and_then(|t| t.body.as_ref()) {
+                let tab_id = tab
+                    .tab_properties
+                    .as_ref()
+                    .and_then(|p| p.tab_id.as_deref());
+                out.push((tab_id, body));
+            }
+            visit(&tab.child_tabs, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    if document.tabs.is_empty() {
+        out.extend(document.body.iter().map(|body| (None, body)));
+    } else {
+        visit(&document.tabs, &mut out);
+    }
+    out
+}
+
+/// Bodies, headers, footers and footnotes of a document with the IDs of
+/// their tab and segment, each tab's body first. The segment ID of a body is
+/// `None`.
+pub(super) fn segments(
+    document: &Document,
+) -> Vec<(Option<&str>, Option<&str>, &[StructuralElement])> {
+    type Segments<'a> = Vec<(Option<&'a str>, Option<&'a str>, &'a [StructuralElement])>;
+    fn visit<'a>(tabs: &'a [Tab], out: &mut Segments<'a>) {
+        for tab in tabs {
+            if let Some(document_tab) = &tab.document_tab {
+                let tab_id = tab
+                    .tab_properties
+                    .as_ref()
+                    .and_then(|p| p.tab_id.as_deref());
+                if let Some(body) = &document_tab.body {
+                    out.push((tab_id, None, &body.content));
+                }
+                let others = document_tab
+                    .headers
+                    .iter()
+                    .chain(&document_tab.footers)
+                    .chain(&document_tab.footnotes);
+                for (segment_id, segment) in others {
+                    out.push((tab_id, Some(segment_id), &segment.content));
+                }
+            }
+            visit(&tab.child_tabs, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    if document.tabs.is_empty() {
+        out.extend(
+            document
+                .body
+                .iter()
+                .map(|body| (None, None, body.content.as_slice())),
+        );
+    } else {
+        visit(&document.tabs, &mut out);
+    }
+    out
+}
+
+/// Mutable bodies of a document, in the same order as [`bodies`]
+fn bodies_mut(document: &mut Document) -> Vec<&mut DocumentBody> {
+    fn visit<'a>(tabs: &'a mut [Tab], out: &mut Vec<&'a mut DocumentBody>) {
+        for tab in tabs {
+            if let Some(body) = tab.document_tab.as_mut().and_then(|t| t.body.as_mut()) {
+                out.push(body);
+            }
+            visit(&mut tab.child_tabs, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    if document.tabs.is_empty() {
+        out.extend(document.body.as_mut());
+    } else {
+        visit(&mut document.tabs, &mut out);
+    }
+    out
+}
+
+/// A heading and the elements of its section, which runs to the next heading
+/// of the same or a higher level or the end of the tab
+pub(super) struct Section<'a> {
+    pub(super) heading: HeadingOutput,
+    pub(super) elements: &'a [StructuralElement],
+}
+
+/// Every section of a document, in the order of their headings
+pub(super) fn document_sections(document: &Document) -> Vec<Section<'_>> {
+    let mut sections = Vec::new();
+    for (tab_id, body) in bodies(document) {
+        let content = &body.content;
+        let headings: Vec<(usize, u8, &Paragraph)> = content
+            .iter()
+            .enumerate()
+            .filter_map(|(position, element)| {
+                let paragraph = element.paragraph.as_ref()?;
+                Some((position, heading_level(paragraph)?, paragraph))
+            })
+            .collect();
+
+        for (n, &(position, level, paragraph)) in headings.iter().enumerate() {
+            let end = headings[n + 1..]
+                .iter()
+                .find(|(_, next_level, _)| *next_level <= level)
+                .map_or(content.len(), |(next, _, _)| *next);
+            let elements = &content[position..end];
+            sections.push(Section {
+                heading: HeadingOutput {
+                    level,
+                    text: paragraph_text(paragraph).trim().to_string(),
+                    heading_id: paragraph
+                        .paragraph_style
+                        .as_ref()
+                        .and_then(|style| style.heading_id.clone()),
+                    tab_id: tab_id.map(str::to_string),
+                    start_index: content[position].start_index,
+                    end_index: elements.last().and_then(|element| element.end_index),
+                },
+                elements,
+            });
+        }
+    }
+    sections
+}
+
+/// The headings of a document, in order
+pub(super) fn document_headings(document: &Document) -> Vec<HeadingOutput> {
+    document_sections(document)
+        .into_iter()
+        .map(|section| section.heading)
+        .collect()
+}
+
+/// Narrow a document to one tab and its child tabs
+pub(super) fn select_tab(document: &mut Document, tab_id: &str) -> Result<(), String> {
+    match find_tab(&document.tabs, tab_id) {
+        Some(tab) => {
+            document.tabs = vec![tab.clone()];
+            Ok(())
+        }
+        None => Err(format!(
+            "Document {} has no tab {}",
+            document.document_id, tab_id
+        )),
+    }
+}
+
+/// Narrow a document to the sections under the named headings, matched
+/// ignoring case. A section runs up to the next heading of the same or a
+/// higher level, or the end of its tab.
+pub(super) fn select_sections(document: &mut Document, sections: &[String]) -> Result<(), String> {
+    let wanted: Vec<String> = sections
+        .iter()
+        .map(|section| section.trim().to_lowercase())
+        .collect();
+    let mut found = vec![false; wanted.len()];
+
+    for body in bodies_mut(document) {
+        // Level of the heading whose section is being kept
+        let mut open: Option<u8> = None;
+        body.content.retain(|element| {
+            let Some(paragraph) = &element.paragraph else {
+                return open.is_some();
+            };
+            if let Some(level) = heading_level(paragraph) {
+                if open.is_some_and(|open| level <= open) {
+                    open = None;
+                }
+                let text = paragraph_text(paragraph).trim().to_lowercase();
+                if let Some(position) = wanted.iter().position(|wanted| *wanted == text) {
+                    found[position] = true;
+                    open.get_or_insert(level);
+                }
+            }
+            open.is_some()
+        });
+    }
+
+    let missing: Vec<String> = sections
+        .iter()
+        .zip(&found)
+        .filter(|(_, found)| !**found)
+        .map(|(section, _)| format!("\"{}\"", section.trim()))
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(format!(
+            "No heading matches {}; pass outline: true to list the headings",
+            missing.join(", ")
+        ))
+    }
+}
+
+/// Narrow a document to one page of whole paragraphs totalling at most
+/// `max_chars` characters, starting where `cursor` left off. A page always
+/// holds at least one paragraph, so a single long paragraph may exceed the
+/// limit. `scope` is the [`page_scope`] of the tab and sections the document
+/// was narrowed to, which the cursor must have been issued for.
+pub(super) fn read_page(
+    document: &mut Document,
+    cursor: Option<&str>,
+    max_chars: usize,
+    scope: u64,
+) -> Result<PageOutput, String> {
+    if max_chars == 0 {
+        return Err("max_chars must be greater than 0".to_string());
+    }
+
+    let revision_id = document.revision_id.clone().unwrap_or_default();
+    let first = match cursor {
+        Some(cursor) => {
+            let cursor = Cursor::decode(cursor)?;
+            if cursor.revision_id != revision_id {
+                return Err(format!(
+                    "The document changed since this cursor was issued (revision {} is now {}); \
+                     read it again without a cursor",
+                    cursor.revision_id, revision_id
+                ));
+            }
+            if cursor.scope != scope {
+                return Err(
+                    "This cursor was issued for a different tab or sections; pass the same \
+                     document_id and sections as for the earlier pages"
+                        .to_string(),
+                );
+            }
+            cursor.paragraph
+        }
+        None => 0,
+    };
+
+    let mut index = 0;
+    let mut used = 0;
+    let mut paragraphs = 0;
+    let mut full = false;
+    for body in bodies_mut(document) {
+        body.content.retain(|element| {
+            let Some(paragraph) = &element.paragraph else {
+                return paragraphs > 0 && !full;
+            };
+            let chars = paragraph_text(paragraph).chars().count();
+            let keep = index >= first && !full && (paragraphs == 0 || used + chars <= max_chars);
+            if keep {
+                used += chars;
+                paragraphs += 1;
+            } else if index >= first {
+                full = true;
+            }
+            index += 1;
+            keep
+        });
+    }
+
+    let total_paragraphs = index;
+    if first > 0 && first >= total_paragraphs {
+        return Err(format!(
+            "The cursor points past the end of the document ({} paragraphs)",
+            total_paragraphs
+        ));
+    }
+
+    let next = first + paragraphs;
+    Ok(PageOutput {
+        first_paragraph: first,
+        paragraphs,
+        total_paragraphs,
+        next_cursor: (next < total_paragraphs).then(|| {
+            Cursor {
+                revision_id,
+                scope,
+                paragraph: next,
+            }
+            .encode()
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A document without tabs made of (style, text) paragraphs
+    fn document(paragraphs: &[(&str, &str)]) -> Document {
+        let content: Vec<serde_json::Value> = paragraphs
+            .iter()
+            .map(|(style, text)| {
+                serde_json::json!({
+                    "paragraph": {
+                        "elements": [{ "textRun": { "content": format!("{}\n", text) } }],
+                        "paragraphStyle": { "namedStyleType": style }
+                    }
+                })
+            })
+            .collect();
+        serde_json::from_value(serde_json::json!({
+            "documentId": "doc1",
+            "title": "Doc",
+            "revisionId": "rev1",
+            "body": { "content": content }
+        }))
+        .unwrap()
+    }
+
+    fn texts(document: &Document) -> Vec<String> {
+        bodies(document)
+            .into_iter()
+            .flat_map(|(_, body)| &body.content)
+            .filter_map(|element| element.paragraph.as_ref())
+            .map(|paragraph| paragraph_text(paragraph).trim().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn pages_follow_each_other_at_paragraph_boundaries() {
+        // Given: Five paragraphs of five characters each, newline included
+        let paragraphs = [("NORMAL_TEXT", "aaaa"); 5];
+
+        // When: Reading pages of at most 12 characters
+        let mut first = document(&paragraphs);
+        let page = read_page(&mut first, None, 12, 0).unwrap();
+        let mut second = document(&paragraphs);
+        let next = read_page(&mut second, page.next_cursor.as_deref(), 12, 0).unwrap();
+
+        // Then: Each page should hold two whole paragraphs and point at the next
+        assert_eq!((page.first_paragraph, page.paragraphs), (0, 2));
+        assert_eq!((next.first_paragraph, next.paragraphs), (2, 2));
+        assert_eq!(next.total_paragraphs, 5);
+        assert_eq!(texts(&second), ["aaaa", "aaaa"]);
+        assert!(next.next_cursor.is_some());
+    }
+
+    #[test]
+    fn stale_cursors_are_rejected() {
+        // Given: A cursor issued for an earlier revision
+        let cursor = Cursor {
+            revision_id: "rev0".to_string(),
+            scope: 0,
+            paragraph: 1,
+        }
+        .encode();
+
+        // When: Reading the next page of the current revision
+        let err = read_page(
+            &mut document(&[("NORMAL_TEXT", "a")]),
+            Some(&cursor),
+            100,
+            0,
+        )
+        .unwrap_err();
+
+        // Then: The change should be reported
+        assert!(err.contains("changed"));
+    }
+
+    #[test]
+    fn cursors_are_bound_to_their_sections() {
+        // Given: A cursor issued while reading the "Intro" section
+        let intro = page_scope(None, Some(&["Intro".to_string()]));
+        let cursor = Cursor {
+            revision_id: "rev1".to_string(),
+            scope: intro,
+            paragraph: 1,
+        }
+        .encode();
+        let paragraphs = [("NORMAL_TEXT", "a"), ("NORMAL_TEXT", "b")];
+
+        // When: Reusing it for the whole document and for the same section
+        let whole = page_scope(None, None);
+        let err = read_page(&mut document(&paragraphs), Some(&cursor), 100, whole).unwrap_err();
+        let same = page_scope(None, Some(&[" intro ".to_string()]));
+        let page = read_page(&mut document(&paragraphs), Some(&cursor), 100, same);
+
+        // Then: Only the matching selection should be accepted
+        assert!(err.contains("different tab or sections"));
+        assert_eq!(page.unwrap().first_paragraph, 1);
+    }
+
+    #[test]
+    fn page_scopes_are_stable_across_builds() {
+        // Given: A tab and section selection
+        let sections = ["Intro".to_string()];
+
+        // When: Fingerprinting it, and the whole document
+        let scope = page_scope(Some("t.0"), Some(&sections));
+        let whole = page_scope(None, None);
+
+        // Then: The values should be fixed, so cursors survive a server upgrade
+        assert_eq!(scope, 0x8fe7_e89a_66a9_cf82);
+        assert_eq!(whole, 0xcbf2_9ce4_8422_2325);
+        assert_ne!(page_scope(Some("t.0"), None), page_scope(None, None));
+    }
+
+    #[test]
+    fn heading_ranges_cover_their_sections() {
+        // Given: A section with a subsection, followed by another section
+        let mut document = document(&[
+            ("HEADING_1", "Intro"),
+            ("HEADING_2", "Details"),
+            ("NORMAL_TEXT", "More"),
+            ("HEADING_1", "Appendix"),
+        ]);
+        let body = document.body.as_mut().unwrap();
+        for (position, element) in body.content.iter_mut().enumerate() {
+            element.start_index = Some(position as i32 * 10 + 1);
+            element.end_index = Some(position as i32 * 10 + 11);
+        }
+
+        // When: Listing the headings
+        let headings = document_headings(&document);
+
+        // Then: Each range should run to the next heading of the same or a higher level
+        let ranges: Vec<(u8, Option<i32>, Option<i32>)> = headings
+            .iter()
+            .map(|heading| (heading.level, heading.start_index, heading.end_index))
+            .collect();
+        assert_eq!(
+            ranges,
+            [
+                (1, Some(1), Some(31)),
+                (2, Some(11), Some(31)),
+                (1, Some(31), Some(41))
+            ]
+        );
+    }
+
+    #[test]
+    fn sections_end_at_the_next_heading_of_the_same_level() {
+        // Given: Two top-level sections, the first with a subsection
+        let mut document = document(&[
+            ("HEADING_1", "Intro"),
+            ("NORMAL_TEXT", "Hello"),
+            ("HEADING_2", "Details"),
+            ("NORMAL_TEXT", "More"),
+            ("HEADING_1", "Appendix"),
+            ("NORMAL_TEXT", "Extra"),
+        ]);
+
+        // When: Selecting the first section
+        select_sections(&mut document, &["intro".to_string()]).unwrap();
+
+        // Then: Its subsection should be kept and the next section dropped
+        assert_eq!(texts(&document), ["Intro", "Hello", "Details", "More"]);
+    }
+}
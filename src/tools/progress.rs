@@ -0,0 +1,57 @@
+use rmcp::{Peer, RoleServer, model::*, service::RequestContext};
+use std::future::Future;
+use tokio_util::sync::CancellationToken;
+
+/// Progress reporting and cancellation for one tool call.
+///
+/// Progress notifications are only sent when the client supplied a progress
+/// token with the request.
+pub(super) struct CallProgress {
+    peer: Peer<RoleServer>,
+    token: Option<ProgressToken>,
+    cancel: CancellationToken,
+    total: u32,
+}
+
+impl CallProgress {
+    /// Track a call made up of `total` steps
+    pub(super) fn new(context: &RequestContext<RoleServer>, total: u32) -> Self {
+        Self {
+            peer: context.peer.clone(),
+            token: context.meta.get_progress_token(),
+            cancel: context.ct.clone(),
+            total,
+        }
+    }
+
+    /// Tell the client that `done` steps are complete and what happens next
+    pub(super) async fn report(&self, done: u32, message: impl Into<String>) {
+        let Some(token) = self.token.clone() else {
+            return;
+        };
+        let notification = ProgressNotificationParam {
+            progress_token: token,
+            progress: f64::from(done),
+            total: Some(f64::from(self.total)),
+            message: Some(message.into()),
+        };
+        if let Err(e) = self.peer.notify_progress(notification).await {
+            tracing::debug!("Failed to send progress notification: {}", e);
+        }
+    }
+
+    /// Run `future` unless the client cancels the call first, in which case
+    /// `None` is returned. The future is dropped on cancellation, which aborts
+    /// any API request it has in flight.
+    pub(super) async fn run<T>(&self, future: impl Future<Output = T>) -> Option<T> {
+        tokio::select! {
+            _ = self.cancel.cancelled() => None,
+            output = future => Some(output),
+        }
+    }
+}
+
+/// Result of a tool call the client cancelled
+pub(super) fn cancelled() -> CallToolResult {
+    CallToolResult::error(vec![Content::text("Cancelled by the client")])
+}
@@ -0,0 +1,277 @@
+use super::GoogleDocsMcpServer;
+use super::documents::format_get_response;
+use crate::config::{DriveFeature, ServerOptions};
+use crate::models::{DocumentReference, ResponseFormat};
+use rmcp::{ErrorData as McpError, model::*};
+
+/// Workflows offered as MCP prompts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DocumentPrompt {
+    Summarize,
+    MeetingNotes,
+    ReviewAndComment,
+    ApplyStyleGuide,
+}
+
+impl DocumentPrompt {
+    const ALL: &'static [DocumentPrompt] = &[
+        DocumentPrompt::Summarize,
+        DocumentPrompt::MeetingNotes,
+        DocumentPrompt::ReviewAndComment,
+        DocumentPrompt::ApplyStyleGuide,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            DocumentPrompt::Summarize => "summarize_document",
+            DocumentPrompt::MeetingNotes => "draft_meeting_notes",
+            DocumentPrompt::ReviewAndComment => "review_and_comment",
+            DocumentPrompt::ApplyStyleGuide => "apply_style_guide",
+        }
+    }
+
+    fn description(self) -> &'static str {
+        match self {
+            DocumentPrompt::Summarize => "Summarize a Google Document",
+            DocumentPrompt::MeetingNotes => {
+                "Turn rough notes into structured meeting notes and add them to a Google Document"
+            }
+            DocumentPrompt::ReviewAndComment => {
+                "Review a Google Document and leave comments on what should change"
+            }
+            DocumentPrompt::ApplyStyleGuide => {
+                "Check a Google Document against a style guide and fix what does not comply"
+            }
+        }
+    }
+
+    /// Arguments besides `document_id`, as (name, description, required)
+    fn extra_arguments(self) -> &'static [(&'static str, &'static str, bool)] {
+        match self {
+            DocumentPrompt::Summarize => &[(
+                "focus",
+                "What the summary should concentrate on, e.g. decisions or open questions",
+                false,
+            )],
+            DocumentPrompt::MeetingNotes => &[
+                ("notes", "Rough notes or a transcript of the meeting", true),
+                ("meeting_title", "Title of the meeting", false),
+            ],
+            DocumentPrompt::ReviewAndComment => &[(
+                "focus",
+                "What to review for, e.g. clarity, accuracy or tone",
+                false,
+            )],
+            DocumentPrompt::ApplyStyleGuide => {
+                &[("style_guide", "The style guide rules to apply", true)]
+            }
+        }
+    }
+
+    fn prompt(self) -> Prompt {
+        let mut arguments = vec![PromptArgument {
+            name: "document_id".to_string(),
+            title: None,
+            description: Some("The document ID".to_string()),
+            required: Some(true),
+        }];
+        arguments.extend(
+            self.extra_arguments()
+                .iter()
+                .map(|(name, description, required)| PromptArgument {
+                    name: name.to_string(),
+                    title: None,
+                    description: Some(description.to_string()),
+                    required: Some(*required),
+                }),
+        );
+        Prompt::new(self.name(), Some(self.description()), Some(arguments))
+    }
+
+    /// Instructions following the embedded document
+    fn instructions(
+        self,
+        arguments: &JsonObject,
+        options: &ServerOptions,
+    ) -> Result<String, String> {
+        let can_edit = !options.read_only;
+        let can_comment = can_edit && options.drive_enabled(DriveFeature::Comments);
+
+        Ok(match self {
+            DocumentPrompt::Summarize => {
+                let mut text = "Summarize the document above. Start with a one-sentence overview, \
+                                then list the key points, decisions and open questions."
+                    .to_string();
+                if let Some(focus) = argument(arguments, "focus") {
+                    text.push_str(&format!("\n\nConcentrate on: {}", focus));
+                }
+                text
+            }
+            DocumentPrompt::MeetingNotes => {
+                let notes = required_argument(arguments, "notes")?;
+                let title = argument(arguments, "meeting_title").unwrap_or("the meeting");
+                let mut text = format!(
+                    "Turn these notes from {} into meeting notes with sections for attendees, \
+                     agenda, discussion, decisions and action items (with owners and due dates \
+                     where given). Match the headings and style already used in the document above.\n\n\
+                     Notes:\n\n{}",
+                    title, notes
+                );
+                if can_edit {
+                    text.push_str(
+                        "\n\nThen append the meeting notes to the end of the document with \
+                         google_docs_update_document.",
+                    );
+                } else {
+                    text.push_str(
+                        "\n\nThe server is read-only, so reply with the meeting notes instead of \
+                         editing the document.",
+                    );
+                }
+                text
+            }
+            DocumentPrompt::ReviewAndComment => {
+                let mut text = "Review the document above for clarity, accuracy, structure and \
+                                tone. For each issue, quote the passage and explain what should change."
+                    .to_string();
+                if let Some(focus) = argument(arguments, "focus") {
+                    text.push_str(&format!("\n\nConcentrate on: {}", focus));
+                }
+                if can_comment {
+                    text.push_str(
+                        "\n\nLeave each finding as a comment with google_docs_add_comment, \
+                         passing the passage as quoted_text.",
+                    );
+                } else {
+                    text.push_str("\n\nReply with the findings as a list.");
+                }
+                text
+            }
+            DocumentPrompt::ApplyStyleGuide => {
+                let style_guide = required_argument(arguments, "style_guide")?;
+                let mut text = format!(
+                    "Check the document above against this style guide and list every passage \
+                     that does not comply, with the rule it breaks and a corrected version.\n\n\
+                     Style guide:\n\n{}",
+                    style_guide
+                );
+                if can_edit {
+                    text.push_str(
+                        "\n\nThen fix the passages with google_docs_update_document, preferring \
+                         replaceAllText with the exact original wording.",
+                    );
+                }
+                text
+            }
+        })
+    }
+}
+
+/// A non-empty string argument
+fn argument<'a>(arguments: &'a JsonObject, name: &str) -> Option<&'a str> {
+    arguments
+        .get(name)
+        .and_then(|value| value.as_str())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn required_argument<'a>(arguments: &'a JsonObject, name: &str) -> Result<&'a str, String> {
+    argument(arguments, name).ok_or_else(|| format!("Missing required argument {}", name))
+}
+
+impl GoogleDocsMcpServer {
+    /// The workflow prompts this server offers
+    pub(super) fn document_prompts() -> Vec<Prompt> {
+        DocumentPrompt::ALL
+            .iter()
+            .map(|prompt| prompt.prompt())
+            .collect()
+    }
+
+    /// Build a workflow prompt around the current content of a document
+    pub(super) async fn get_document_prompt(
+        &self,
+        extensions: &Extensions,
+        name: &str,
+        arguments: Option<JsonObject>,
+    ) -> Result<GetPromptResult, McpError> {
+        let prompt = DocumentPrompt::ALL
+            .iter()
+            .copied()
+            .find(|prompt| prompt.name() == name)
+            .ok_or_else(|| McpError::invalid_params(format!("Unknown prompt {}", name), None))?;
+        let arguments = arguments.unwrap_or_default();
+
+        let document_id = required_argument(&arguments, "document_id")
+            .and_then(DocumentReference::parse_document_id)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+        let instructions = prompt
+            .instructions(&arguments, &self.options)
+            .map_err(|e| McpError::invalid_params(e, None))?;
+
+        let client = self.client_for(extensions, None)?;
+        let document = client.get_document(&document_id).await?;
+
+        Ok(GetPromptResult {
+            description: Some(format!("{}: {}", prompt.description(), document.title)),
+            messages: vec![
+                PromptMessage::new_resource(
+                    PromptMessageRole::User,
+                    DocumentReference::resource_uri(&document.document_id),
+                    Some("text/markdown".to_string()),
+                    Some(format_get_response(
+                        &document,
+                        None,
+                        &ResponseFormat::Markdown,
+                    )),
+                    None,
+                    None,
+                    None,
+                ),
+                PromptMessage::new_text(PromptMessageRole::User, instructions),
+            ],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn review_prompt_only_suggests_comments_when_available() {
+        // Given: Review arguments and servers with and without the comments feature
+        let arguments = JsonObject::new();
+        let with_comments = ServerOptions {
+            drive_features: [DriveFeature::Comments].into(),
+            ..Default::default()
+        };
+
+        // When: Building the review instructions
+        let commented = DocumentPrompt::ReviewAndComment
+            .instructions(&arguments, &with_comments)
+            .unwrap();
+        let listed = DocumentPrompt::ReviewAndComment
+            .instructions(&arguments, &ServerOptions::default())
+            .unwrap();
+
+        // Then: Only the first should point at the comment tool
+        assert!(commented.contains("google_docs_add_comment"));
+        assert!(!listed.contains("google_docs_add_comment"));
+    }
+
+    #[test]
+    fn meeting_notes_prompt_requires_notes() {
+        // Given: No notes
+        let arguments = JsonObject::new();
+
+        // When: Building the meeting notes instructions
+        let err = DocumentPrompt::MeetingNotes
+            .instructions(&arguments, &ServerOptions::default())
+            .unwrap_err();
+
+        // Then: The missing argument should be named
+        assert!(err.contains("notes"));
+    }
+}
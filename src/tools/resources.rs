@@ -0,0 +1,259 @@
+use super::GoogleDocsMcpServer;
+use super::documents::{extract_text_from_tab, format_get_response};
+use crate::api::{DriveSearch, GoogleDocsClient};
+use crate::config::DriveFeature;
+use crate::models::{Document, DocumentReference, RESOURCE_URI_PREFIX, ResponseFormat, Tab};
+use rmcp::{ErrorData as McpError, model::*};
+
+/// MIME type of the markdown rendering of a document
+const MARKDOWN_MIME_TYPE: &str = "text/markdown";
+
+/// MIME type of the raw API JSON of a document
+const JSON_MIME_TYPE: &str = "application/json";
+
+/// Recently modified Drive documents added to the resource list
+const LISTED_DRIVE_DOCUMENTS: u32 = 25;
+
+/// Parse one of the server's `gdocs://document/{id}[/tab/{tabId}][?format=json]`
+/// resource URIs; document IDs and Google Docs URLs are not resources
+pub(super) fn parse_document_resource(uri: &str) -> Result<DocumentReference, McpError> {
+    if !DocumentReference::is_resource_uri(uri) {
+        return Err(McpError::resource_not_found(
+            format!("Unknown resource URI {}", uri),
+            None,
+        ));
+    }
+    DocumentReference::parse(uri).map_err(|e| McpError::resource_not_found(e, None))
+}
+
+impl GoogleDocsMcpServer {
+    /// Resource templates for documents and their tabs
+    pub(super) fn document_resource_templates() -> Vec<ResourceTemplate> {
+        vec![
+            RawResourceTemplate {
+                uri_template: format!("{}{{documentId}}", RESOURCE_URI_PREFIX),
+                name: "Google Doc".to_string(),
+                title: None,
+                description: Some(
+                    "A Google Document rendered as markdown. Append ?format=json for the raw Docs API JSON."
+                        .to_string(),
+                ),
+                mime_type: Some(MARKDOWN_MIME_TYPE.to_string()),
+            }
+            .no_annotation(),
+            RawResourceTemplate {
+                uri_template: format!("{}{{documentId}}/tab/{{tabId}}", RESOURCE_URI_PREFIX),
+                name: "Google Doc tab".to_string(),
+                title: None,
+                description: Some(
+                    "One tab of a Google Document, including its child tabs, rendered as markdown. Append ?format=json for the raw Docs API JSON."
+                        .to_string(),
+                ),
+                mime_type: Some(MARKDOWN_MIME_TYPE.to_string()),
+            }
+            .no_annotation(),
+        ]
+    }
+
+    /// Documents to offer as resources: those recently fetched through the
+    /// caller's client, then recently modified ones from Drive when the
+    /// search feature is enabled
+    pub(super) async fn list_document_resources(
+        &self,
+        extensions: &Extensions,
+    ) -> Result<Vec<Resource>, McpError> {
+        let client = self.client_for(extensions, None)?;
+        let search = DriveSearch {
+            page_size: LISTED_DRIVE_DOCUMENTS,
+            ..Default::default()
+        };
+
+        Ok(self
+            .known_documents(&client, search)
+            .await
+            .into_iter()
+            .map(|(document_id, title)| {
+                RawResource {
+                    uri: DocumentReference::resource_uri(&document_id),
+                    name: title,
+                    description: Some(format!("Google Doc {}", document_id)),
+                    mime_type: Some(MARKDOWN_MIME_TYPE.to_string()),
+                    size: None,
+                    title: None,
+                    icons: None,
+                }
+                .no_annotation()
+            })
+            .collect())
+    }
+
+    /// IDs and titles of the documents recently fetched through `client`,
+    /// followed by those `search` finds in Drive when the search feature is
+    /// enabled
+    pub(super) async fn known_documents(
+        &self,
+        client: &GoogleDocsClient,
+        search: DriveSearch,
+    ) -> Vec<(String, String)> {
+        let mut documents: Vec<(String, String)> = client
+            .recent_documents()
+            .into_iter()
+            .map(|document| (document.document_id, document.title))
+            .collect();
+
+        if self.options.drive_enabled(DriveFeature::Search) {
+            match client.drive().search_documents(&search).await {
+                Ok(list) => {
+                    for file in list.files {
+                        if !documents.iter().any(|(id, _)| *id == file.id) {
+                            let title = file.name.unwrap_or_else(|| file.id.clone());
+                            documents.push((file.id, title));
+                        }
+                    }
+                }
+                Err(e) => tracing::warn!("Failed to list Drive documents: {}", e.message),
+            }
+        }
+
+        documents
+    }
+
+    /// Read a document or tab resource
+    pub(super) async fn read_document_resource(
+        &self,
+        extensions: &Extensions,
+        uri: &str,
+    ) -> Result<ReadResourceResult, McpError> {
+        let parsed = parse_document_resource(uri)?;
+
+        let client = self.client_for(extensions, None)?;
+        let raw = client.get_document_json(&parsed.document_id).await?;
+
+        let (mime_type, text) = match parsed.format {
+            ResponseFormat::Json => {
+                let json = match parsed.tab_id.as_deref() {
+                    Some(tab_id) => find_tab_json(&raw["tabs"], tab_id)
+                        .ok_or_else(|| tab_not_found(&parsed.document_id, tab_id))?,
+                    None => &raw,
+                };
+                (JSON_MIME_TYPE, json.to_string())
+            }
+            ResponseFormat::Markdown => {
+                let document: Document = serde_json::from_value(raw).map_err(|e| {
+                    McpError::internal_error(format!("Failed to parse API response: {}", e), None)
+                })?;
+                let text = match parsed.tab_id.as_deref() {
+                    Some(tab_id) => {
+                        let tab = find_tab(&document.tabs, tab_id)
+                            .ok_or_else(|| tab_not_found(&parsed.document_id, tab_id))?;
+                        format_tab_markdown(&document, tab, tab_id)
+                    }
+                    None => format_get_response(&document, None, &ResponseFormat::Markdown),
+                };
+                (MARKDOWN_MIME_TYPE, text)
+            }
+        };
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::TextResourceContents {
+                uri: uri.to_string(),
+                mime_type: Some(mime_type.to_string()),
+                text,
+                meta: None,
+            }],
+        })
+    }
+}
+
+/// Error for a tab missing from a document
+fn tab_not_found(document_id: &str, tab_id: &str) -> McpError {
+    McpError::resource_not_found(
+        format!("Document {} has no tab {}", document_id, tab_id),
+        None,
+    )
+}
+
+/// Find a tab by ID among tabs and their child tabs
+pub(super) fn find_tab<'a>(tabs: &'a [Tab], tab_id: &str) -> Option<&'a Tab> {
+    tabs.iter().find_map(|tab| {
+        let matches = tab
+            .tab_properties
+            .as_ref()
+            .and_then(|properties| properties.tab_id.as_deref())
+            == Some(tab_id);
+        if matches {
+            Some(tab)
+        } else {
+            find_tab(&tab.child_tabs, tab_id)
+        }
+    })
+}
+
+/// Find a tab by ID in the raw API JSON of a `tabs` array
+fn find_tab_json<'a>(tabs: &'a serde_json::Value, tab_id: &str) -> Option<&'a serde_json::Value> {
+    tabs.as_array()?.iter().find_map(|tab| {
+        if tab["tabProperties"]["tabId"].as_str() == Some(tab_id) {
+            Some(tab)
+        } else {
+            find_tab_json(&tab["childTabs"], tab_id)
+        }
+    })
+}
+
+/// Markdown rendering of one tab of a document
+fn format_tab_markdown(document: &Document, tab: &Tab, tab_id: &str) -> String {
+    let tab_title = tab
+        .tab_properties
+        .as_ref()
+        .and_then(|properties| properties.title.as_deref())
+        .unwrap_or(tab_id);
+    format!(
+        "# {} / {}\n\n\
+         - **Document ID**: `{}`\n\
+         - **Tab ID**: `{}`\n\
+         - **URL**: [Open in Google Docs](https://docs.google.com/document/d/{}/edit?tab={})\n\n\
+         ## Content\n\n\
+         {}",
+        document.title,
+        tab_title,
+        document.document_id,
+        tab_id,
+        document.document_id,
+        tab_id,
+        extract_text_from_tab(tab)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_resource_uris_are_resources() {
+        // Given: A resource URI, a bare document ID and a Google Docs URL
+        // When: Parsing them as resources
+        let resource = parse_document_resource("gdocs://document/abc/tab/t.0?format=json");
+
+        // Then: Only the resource URI should be accepted
+        assert_eq!(resource.unwrap().tab_id.as_deref(), Some("t.0"));
+        assert!(parse_document_resource("abc").is_err());
+        assert!(parse_document_resource("https://docs.google.com/document/d/abc/edit").is_err());
+    }
+
+    #[test]
+    fn finds_nested_tabs_in_raw_json() {
+        // Given: Raw API JSON with a child tab
+        let tabs = serde_json::json!([
+            {
+                "tabProperties": { "tabId": "t.0" },
+                "childTabs": [{ "tabProperties": { "tabId": "t.1", "title": "Notes" } }]
+            }
+        ]);
+
+        // When: Looking up the child tab
+        let tab = find_tab_json(&tabs, "t.1").unwrap();
+
+        // Then: The child tab should be returned
+        assert_eq!(tab["tabProperties"]["title"], "Notes");
+    }
+}
@@ -0,0 +1,310 @@
+use super::GoogleDocsMcpServer;
+use super::documents::structured_result;
+use super::drive::parse_timestamp;
+use crate::models::{
+    DocumentReference, ResponseFormat, Revision, RevisionContentOutput, RevisionOutput,
+    RevisionsOutput,
+};
+use chrono::{DateTime, Utc};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Revisions listed when the caller does not ask for a limit
+const DEFAULT_REVISION_LIMIT: usize = 50;
+
+/// Input for listing a document's revisions
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListRevisionsParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// Only revisions saved at or after this time (RFC 3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub modified_after: Option<String>,
+
+    /// Maximum number of revisions to return, most recent first (default 50)
+    #[serde(default)]
+    pub limit: Option<usize>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for reading a past revision of a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetRevisionParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// The revision ID, as returned by google_docs_list_revisions
+    pub revision_id: String,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+#[tool_router(router = revision_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// List the revisions of a Google Document.
+    #[tool(
+        description = "List the saved revisions of a Google Document, most recent first, with their revision ID, time and the user who made them. Use `modified_after` to see what changed since a point in time.",
+        output_schema = cached_schema_for_type::<RevisionsOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_list_revisions(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ListRevisionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let modified_after = match params.modified_after.as_deref().map(str::trim) {
+            Some(after) if !after.is_empty() => match parse_timestamp("modified_after", after) {
+                Ok(after) => Some(after),
+                Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+            },
+            _ => None,
+        };
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        match client.drive().list_revisions(&document_id).await {
+            Ok(listing) => {
+                let revisions = select_revisions(
+                    listing.items,
+                    modified_after,
+                    params.limit.unwrap_or(DEFAULT_REVISION_LIMIT),
+                );
+                let output = RevisionsOutput {
+                    document_id,
+                    revisions: revisions.iter().map(revision_output).collect(),
+                    truncated: listing.truncated,
+                };
+                let response = format_revisions_response(&output, &params.response_format);
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to list revisions: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Get the plain text of a past revision.
+    #[tool(
+        description = "Get the plain text content of a past revision of a Google Document. Google only keeps exportable content for some revisions; others report that they are unavailable.",
+        output_schema = cached_schema_for_type::<RevisionContentOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_get_revision(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<GetRevisionParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if params.revision_id.trim().is_empty() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Revision ID cannot be empty",
+            )]));
+        }
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let drive = client.drive();
+        let result = match drive.get_revision(&document_id, &params.revision_id).await {
+            Ok(revision) => drive
+                .revision_text(&revision)
+                .await
+                .map(|text| (revision, text)),
+            Err(e) => Err(e),
+        };
+
+        match result {
+            Ok((revision, text)) => {
+                let output = RevisionContentOutput {
+                    document_id,
+                    revision: revision_output(&revision),
+                    content: text,
+                };
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => format!(
+                        "# Revision `{}`\n\n\
+                         - **Document ID**: `{}`\n\
+                         - **Modified**: {}\n\
+                         - **Modified by**: {}\n\n\
+                         ## Content\n\n\
+                         {}",
+                        revision.id,
+                        output.document_id,
+                        revision.modified_time.as_deref().unwrap_or("unknown"),
+                        modified_by(&revision),
+                        output.content
+                    ),
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to get revision: {:?}",
+                e
+            ))])),
+        }
+    }
+}
+
+/// Keep revisions saved at or after `modified_after`, most recent first, up to `limit`
+fn select_revisions(
+    revisions: Vec<Revision>,
+    modified_after: Option<DateTime<Utc>>,
+    limit: usize,
+) -> Vec<Revision> {
+    revisions
+        .into_iter()
+        .rev()
+        .filter(|revision| match modified_after {
+            Some(after) => revision
+                .modified_time
+                .as_deref()
+                .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                .is_some_and(|t| t >= after),
+            None => true,
+        })
+        .take(limit)
+        .collect()
+}
+
+/// The user who made a revision, for display
+fn modified_by(revision: &Revision) -> &str {
+    revision
+        .last_modifying_user
+        .as_ref()
+        .map(|user| user.label())
+        .unwrap_or("unknown")
+}
+
+/// Structured representation of a revision's metadata
+fn revision_output(revision: &Revision) -> RevisionOutput {
+    RevisionOutput {
+        revision_id: revision.id.clone(),
+        modified_time: revision.modified_time.clone(),
+        modified_by: revision
+            .last_modifying_user
+            .as_ref()
+            .map(|user| user.label().to_string()),
+        content_available: revision.export_links.contains_key("text/plain"),
+    }
+}
+
+/// Format list revisions response
+fn format_revisions_response(output: &RevisionsOutput, format: &ResponseFormat) -> String {
+    match format {
+        ResponseFormat::Markdown => {
+            let mut lines = vec![
+                "# Revisions".to_string(),
+                String::new(),
+                format!("- **Document ID**: `{}`", output.document_id),
+                format!("- **Count**: {}", output.revisions.len()),
+                String::new(),
+            ];
+            if output.truncated {
+                lines.push(
+                    "The document has more revisions than could be fetched, and Drive lists \
+                     them oldest first, so the newest revisions are missing from this list."
+                        .to_string(),
+                );
+                lines.push(String::new());
+            }
+            for revision in &output.revisions {
+                lines.push(format!(
+                    "- `{}` {} by {}{}",
+                    revision.revision_id,
+                    revision.modified_time.as_deref().unwrap_or("unknown time"),
+                    revision.modified_by.as_deref().unwrap_or("unknown"),
+                    if revision.content_available {
+                        ""
+                    } else {
+                        " (content unavailable)"
+                    }
+                ));
+            }
+            lines.join("\n")
+        }
+        ResponseFormat::Json => serde_json::json!(output).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn revision(id: &str, modified_time: &str) -> Revision {
+        Revision {
+            id: id.to_string(),
+            modified_time: Some(modified_time.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn revisions_are_filtered_newest_first() {
+        // Given: Three revisions in Drive's oldest-first order
+        let revisions = vec![
+            revision("1", "2024-04-29T09:00:00.000Z"),
+            revision("2", "2024-05-06T09:00:00.000Z"),
+            revision("3", "2024-05-07T09:00:00.000Z"),
+        ];
+        let monday = DateTime::parse_from_rfc3339("2024-05-06T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        // When: Selecting revisions since Monday
+        let selected = select_revisions(revisions, Some(monday), 10);
+
+        // Then: Only this week's revisions remain, most recent first
+        let ids: Vec<&str> = selected.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["3", "2"]);
+    }
+
+    #[test]
+    fn truncated_listings_say_the_newest_revisions_are_missing() {
+        // Given: A listing that stopped at the page cap
+        let output = RevisionsOutput {
+            document_id: "doc".to_string(),
+            revisions: vec![revision_output(&revision("1", "2024-04-29T09:00:00.000Z"))],
+            truncated: true,
+        };
+
+        // When: Formatting it in both formats
+        let markdown = format_revisions_response(&output, &ResponseFormat::Markdown);
+        let json = format_revisions_response(&output, &ResponseFormat::Json);
+
+        // Then: Both should report the truncation
+        assert!(markdown.contains("newest revisions are missing"));
+        assert!(json.contains("\"truncated\":true"));
+    }
+}
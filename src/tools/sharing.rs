@@ -0,0 +1,541 @@
+use super::GoogleDocsMcpServer;
+use super::documents::structured_result;
+use crate::config::SHARE_DOMAINS_ENV;
+use crate::models::{
+    Credentials, DocumentReference, Permission, PermissionOutput, PermissionsOutput,
+    ResponseFormat, RevokeOutput, ShareOutput, WhoamiOutput,
+};
+use rmcp::{
+    ErrorData as McpError, handler::server::common::cached_schema_for_type,
+    handler::server::wrapper::Parameters, model::*, tool, tool_router,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Who a document is shared with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum GranteeType {
+    /// A single user, identified by email address
+    User,
+    /// A Google group, identified by email address
+    Group,
+    /// Everyone in a Workspace domain
+    Domain,
+}
+
+/// Access level granted when sharing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ShareRole {
+    /// Can view
+    Reader,
+    /// Can view and comment
+    Commenter,
+    /// Can edit
+    Writer,
+}
+
+/// Input for listing who has access to a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListPermissionsParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for sharing a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ShareDocumentParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// "user", "group" or "domain"
+    #[serde(rename = "type")]
+    pub grantee_type: GranteeType,
+
+    /// "reader", "commenter" or "writer"
+    pub role: ShareRole,
+
+    /// Email address of the user or group
+    #[serde(default)]
+    pub email_address: Option<String>,
+
+    /// Domain to share with, for type "domain"
+    #[serde(default)]
+    pub domain: Option<String>,
+
+    /// Email the user or group about the share (default: true)
+    #[serde(default = "default_notify")]
+    pub notify: bool,
+
+    /// Message included in the notification email
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+fn default_notify() -> bool {
+    true
+}
+
+/// Input for revoking access to a document
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RevokeAccessParams {
+    /// The document ID or URL
+    pub document_id: String,
+
+    /// ID of the permission to remove, as returned by google_docs_list_permissions
+    #[serde(default)]
+    pub permission_id: Option<String>,
+
+    /// Email address of the user or group to remove, instead of permission_id
+    #[serde(default)]
+    pub email_address: Option<String>,
+
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+/// Input for reporting the account in use
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WhoamiParams {
+    /// Credential profile to use (defaults to the configured default profile)
+    #[serde(default)]
+    pub profile: Option<String>,
+
+    /// Output format: "markdown" (default) or "json"
+    #[serde(default)]
+    pub response_format: ResponseFormat,
+}
+
+#[tool_router(router = sharing_tool_router, vis = "pub(super)")]
+impl GoogleDocsMcpServer {
+    /// List who has access to a Google Document.
+    #[tool(
+        description = "List who has access to a Google Document: permission IDs, type (user, group, domain, anyone), role and email address or domain.",
+        output_schema = cached_schema_for_type::<PermissionsOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_list_permissions(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ListPermissionsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        match client.drive().list_permissions(&document_id).await {
+            Ok(listing) => {
+                let permissions = listing.items;
+                let output = PermissionsOutput {
+                    document_id,
+                    permissions: permissions.iter().map(permission_output).collect(),
+                    truncated: listing.truncated,
+                };
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => {
+                        let mut lines = vec![
+                            "# Permissions".to_string(),
+                            String::new(),
+                            format!("- **Document ID**: `{}`", output.document_id),
+                            String::new(),
+                        ];
+                        lines.extend(permissions.iter().map(|p| {
+                            format!(
+                                "- `{}` {} **{}**: {}",
+                                p.id,
+                                p.permission_type,
+                                p.role,
+                                grantee(p)
+                            )
+                        }));
+                        if output.truncated {
+                            lines.push(String::new());
+                            lines.push(
+                                "The document has more permissions than could be fetched; \
+                                 some are missing from this list."
+                                    .to_string(),
+                            );
+                        }
+                        lines.join("\n")
+                    }
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to list permissions: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Share a Google Document.
+    #[tool(
+        description = "Share a Google Document with a user, group or domain as reader, commenter or writer. Only domains on the server's sharing allowlist can be granted access.",
+        output_schema = cached_schema_for_type::<ShareOutput>(),
+        annotations(destructive_hint = false, idempotent_hint = true)
+    )]
+    async fn google_docs_share(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<ShareDocumentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+
+        let (permission, domain) = match share_request(&params) {
+            Ok(request) => request,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        if !self.options.share_domain_allowed(&domain) {
+            return Ok(CallToolResult::error(vec![Content::text(format!(
+                "Sharing with {} is not allowed; allowed domains are set in {}",
+                domain, SHARE_DOMAINS_ENV
+            ))]));
+        }
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let message = params.message.as_deref().filter(|m| !m.trim().is_empty());
+        match client
+            .drive()
+            .create_permission(&document_id, &permission, params.notify, message)
+            .await
+        {
+            Ok(permission) => {
+                let output = ShareOutput {
+                    document_id,
+                    permission: permission_output(&permission),
+                };
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => format!(
+                        "# Document Shared\n\n\
+                         - **Document ID**: `{}`\n\
+                         - **Shared with**: {} ({})\n\
+                         - **Role**: {}\n\
+                         - **Permission ID**: `{}`\n",
+                        output.document_id,
+                        grantee(&permission),
+                        permission.permission_type,
+                        permission.role,
+                        permission.id
+                    ),
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to share document: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Revoke access to a Google Document.
+    #[tool(
+        description = "Revoke access to a Google Document by permission ID or by the email address of a user or group.",
+        output_schema = cached_schema_for_type::<RevokeOutput>(),
+        annotations(destructive_hint = true, idempotent_hint = true)
+    )]
+    async fn google_docs_revoke_access(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<RevokeAccessParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let document_id = match DocumentReference::parse_document_id(&params.document_id) {
+            Ok(document_id) => document_id,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e)])),
+        };
+        let permission_id = params
+            .permission_id
+            .as_deref()
+            .map(str::trim)
+            .filter(|id| !id.is_empty());
+        let email_address = params
+            .email_address
+            .as_deref()
+            .map(str::trim)
+            .filter(|email| !email.is_empty());
+        if permission_id.is_some() == email_address.is_some() {
+            return Ok(CallToolResult::error(vec![Content::text(
+                "Set exactly one of permission_id or email_address",
+            )]));
+        }
+
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+        let drive = client.drive();
+
+        let permission_id = match (permission_id, email_address) {
+            (Some(id), _) => id.to_string(),
+            (None, Some(email)) => match drive.list_permissions(&document_id).await {
+                Ok(listing) => match listing.items.iter().find(|p| {
+                    p.email_address
+                        .as_deref()
+                        .is_some_and(|e| e.eq_ignore_ascii_case(email))
+                }) {
+                    Some(permission) if permission.role == "owner" => {
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "{} owns the document; ownership cannot be revoked",
+                            email
+                        ))]));
+                    }
+                    Some(permission) => permission.id.clone(),
+                    None if listing.truncated => {
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "{} was not found among the document's first permissions and the \
+                             rest could not be fetched; revoke it by permission_id instead",
+                            email
+                        ))]));
+                    }
+                    None => {
+                        return Ok(CallToolResult::error(vec![Content::text(format!(
+                            "{} has no direct access to the document",
+                            email
+                        ))]));
+                    }
+                },
+                Err(e) => {
+                    return Ok(CallToolResult::error(vec![Content::text(format!(
+                        "Failed to list permissions: {:?}",
+                        e
+                    ))]));
+                }
+            },
+            (None, None) => unreachable!("checked above"),
+        };
+
+        match drive.delete_permission(&document_id, &permission_id).await {
+            Ok(()) => {
+                let output = RevokeOutput {
+                    document_id,
+                    permission_id,
+                    revoked: true,
+                };
+                let response = match params.response_format {
+                    ResponseFormat::Markdown => format!(
+                        "# Access Revoked\n\n\
+                         - **Document ID**: `{}`\n\
+                         - **Permission ID**: `{}`\n",
+                        output.document_id, output.permission_id
+                    ),
+                    ResponseFormat::Json => serde_json::json!(output).to_string(),
+                };
+                Ok(structured_result(response, &output))
+            }
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(format!(
+                "Failed to revoke access: {:?}",
+                e
+            ))])),
+        }
+    }
+
+    /// Report the Google account this server acts as.
+    #[tool(
+        description = "Report the Google account this server acts as: the service account email that documents must be shared with, and the impersonated user if any.",
+        output_schema = cached_schema_for_type::<WhoamiOutput>(),
+        annotations(read_only_hint = true)
+    )]
+    async fn google_docs_whoami(
+        &self,
+        extensions: Extensions,
+        Parameters(params): Parameters<WhoamiParams>,
+    ) -> Result<CallToolResult, McpError> {
+        let client = match self.client_for(&extensions, params.profile.as_deref()) {
+            Ok(client) => client,
+            Err(e) => return Ok(CallToolResult::error(vec![Content::text(e.message)])),
+        };
+
+        let (credential_type, client_email) = match client.credentials() {
+            Credentials::ServiceAccount(creds) => {
+                ("service_account", Some(creds.client_email.as_str()))
+            }
+            Credentials::AuthorizedUser(_) => ("authorized_user", None),
+        };
+        let subject = client.subject();
+        let share_with = subject.or(client_email);
+        let output = WhoamiOutput {
+            credential_type: credential_type.to_string(),
+            client_email: client_email.map(str::to_string),
+            subject: subject.map(str::to_string),
+            share_with: share_with.map(str::to_string),
+            scopes: client.scopes().to_vec(),
+        };
+
+        let response = match params.response_format {
+            ResponseFormat::Markdown => {
+                let mut lines = vec![
+                    "# Google Account".to_string(),
+                    String::new(),
+                    format!("- **Credential type**: {}", credential_type),
+                ];
+                if let Some(email) = client_email {
+                    lines.push(format!("- **Service account**: `{}`", email));
+                }
+                if let Some(subject) = subject {
+                    lines.push(format!("- **Impersonating**: `{}`", subject));
+                }
+                lines.push(format!("- **Scopes**: {}", client.scopes().join(", ")));
+                lines.push(String::new());
+                lines.push(match share_with {
+                    Some(email) => format!(
+                        "Share documents with `{}` to make them accessible to this server.",
+                        email
+                    ),
+                    None => "This server acts as the user who authorized its credentials; \
+                             documents that user can open are accessible."
+                        .to_string(),
+                });
+                lines.join("\n")
+            }
+            ResponseFormat::Json => serde_json::json!(output).to_string(),
+        };
+        Ok(structured_result(response, &output))
+    }
+}
+
+/// Build the Drive permission resource for a share request, along with the
+/// domain that must be on the allowlist
+fn share_request(params: &ShareDocumentParams) -> Result<(serde_json::Value, String), String> {
+    let role = match params.role {
+        ShareRole::Reader => "reader",
+        ShareRole::Commenter => "commenter",
+        ShareRole::Writer => "writer",
+    };
+
+    match params.grantee_type {
+        GranteeType::User | GranteeType::Group => {
+            let email = params
+                .email_address
+                .as_deref()
+                .map(str::trim)
+                .filter(|email| !email.is_empty())
+                .ok_or("email_address is required when sharing with a user or group")?;
+            let domain = email
+                .rsplit_once('@')
+                .map(|(_, domain)| domain)
+                .filter(|domain| !domain.is_empty())
+                .ok_or_else(|| format!("{:?} is not an email address", email))?;
+            let grantee_type = if params.grantee_type == GranteeType::User {
+                "user"
+            } else {
+                "group"
+            };
+            Ok((
+                serde_json::json!({ "type": grantee_type, "role": role, "emailAddress": email }),
+                domain.to_string(),
+            ))
+        }
+        GranteeType::Domain => {
+            let domain = params
+                .domain
+                .as_deref()
+                .map(str::trim)
+                .filter(|domain| !domain.is_empty())
+                .ok_or("domain is required when sharing with a domain")?;
+            Ok((
+                serde_json::json!({ "type": "domain", "role": role, "domain": domain }),
+                domain.to_string(),
+            ))
+        }
+    }
+}
+
+/// Who a permission grants access to, for display
+fn grantee(permission: &Permission) -> &str {
+    permission
+        .email_address
+        .as_deref()
+        .or(permission.domain.as_deref())
+        .or(permission.display_name.as_deref())
+        .unwrap_or("anyone with the link")
+}
+
+/// Structured representation of a permission
+fn permission_output(permission: &Permission) -> PermissionOutput {
+    PermissionOutput {
+        permission_id: permission.id.clone(),
+        permission_type: permission.permission_type.clone(),
+        role: permission.role.clone(),
+        email_address: permission.email_address.clone(),
+        domain: permission.domain.clone(),
+        display_name: permission.display_name.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn share_request_checks_the_email_domain() {
+        // Given: A request to share with a user
+        let params: ShareDocumentParams = serde_json::from_value(serde_json::json!({
+            "document_id": "doc",
+            "type": "user",
+            "role": "commenter",
+            "email_address": "bob@Partner.example"
+        }))
+        .unwrap();
+
+        // When: Building the permission
+        let (permission, domain) = share_request(&params).unwrap();
+
+        // Then: The user's domain is what the allowlist is checked against
+        assert_eq!(domain, "Partner.example");
+        assert_eq!(permission["type"], "user");
+        assert_eq!(permission["role"], "commenter");
+    }
+
+    #[test]
+    fn domain_share_requires_a_domain() {
+        // Given: A domain share without the domain
+        let params: ShareDocumentParams = serde_json::from_value(serde_json::json!({
+            "document_id": "doc",
+            "type": "domain",
+            "role": "reader"
+        }))
+        .unwrap();
+
+        // When: Building the permission
+        let err = share_request(&params).unwrap_err();
+
+        // Then: The missing field should be named
+        assert!(err.contains("domain is required"));
+    }
+}
@@ -0,0 +1,150 @@
+use super::GoogleDocsMcpServer;
+use super::resources::parse_document_resource;
+use crate::api::GoogleDocsClient;
+use rmcp::{ErrorData as McpError, Peer, RoleServer, model::*};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::{CancellationToken, DropGuard};
+
+/// Resource subscriptions of one MCP session, each polled by its own task.
+///
+/// Dropping a subscription stops its task. Clones share the same
+/// subscriptions; each MCP session starts from
+/// [`GoogleDocsMcpServer::for_session`] with an empty set.
+#[derive(Clone, Default)]
+pub(super) struct Subscriptions(Arc<Mutex<HashMap<String, DropGuard>>>);
+
+impl Subscriptions {
+    /// Track the task polling `uri`, stopping any earlier one
+    fn insert(&self, uri: &str, cancel: CancellationToken) {
+        self.lock().insert(uri.to_string(), cancel.drop_guard());
+    }
+
+    /// Stop polling `uri`
+    fn remove(&self, uri: &str) {
+        self.lock().remove(uri);
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, DropGuard>> {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl GoogleDocsMcpServer {
+    /// Start polling a document resource, notifying `peer` when it changes
+    pub(super) async fn subscribe_document(
+        &self,
+        extensions: &Extensions,
+        peer: Peer<RoleServer>,
+        uri: &str,
+    ) -> Result<(), McpError> {
+        let parsed = parse_document_resource(uri)?;
+        let client = self.client_for(extensions, None)?;
+
+        // Fetching the current revision up front also checks the document is accessible
+        let revision_id = client.get_revision_id(&parsed.document_id).await?;
+
+        let cancel = CancellationToken::new();
+        tokio::spawn(watch_document(
+            client,
+            peer,
+            uri.to_string(),
+            parsed.document_id,
+            revision_id,
+            self.options.poll_interval,
+            cancel.clone(),
+        ));
+        self.subscriptions.insert(uri, cancel);
+        Ok(())
+    }
+
+    /// Stop polling a document resource
+    pub(super) fn unsubscribe_document(&self, uri: &str) {
+        self.subscriptions.remove(uri);
+    }
+}
+
+/// Poll a document's revision ID until cancelled or the client goes away,
+/// sending a resource updated notification for `uri` whenever it changes
+async fn watch_document(
+    client: Arc<GoogleDocsClient>,
+    peer: Peer<RoleServer>,
+    uri: String,
+    document_id: String,
+    mut revision_id: Option<String>,
+    interval: Duration,
+    cancel: CancellationToken,
+) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = ticker.tick() => {}
+        }
+
+        let current = match client.get_revision_id(&document_id).await {
+            Ok(current) => current,
+            Err(e) => {
+                tracing::warn!("Failed to check {} for changes: {}", uri, e.message);
+                continue;
+            }
+        };
+        if current == revision_id {
+            continue;
+        }
+        revision_id = current;
+
+        tracing::debug!("{} changed", uri);
+        let notification = ResourceUpdatedNotificationParam { uri: uri.clone() };
+        if let Err(e) = peer.notify_resource_updated(notification).await {
+            tracing::debug!("Stopped watching {}: {}", uri, e);
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resubscribing_stops_the_previous_task() {
+        // Given: A subscription whose task watches a cancellation token
+        let subscriptions = Subscriptions::default();
+        let first = CancellationToken::new();
+        subscriptions.insert("gdocs://document/abc", first.clone());
+
+        // When: Subscribing to the same URI again
+        let second = CancellationToken::new();
+        subscriptions.insert("gdocs://document/abc", second.clone());
+
+        // Then: Only the earlier task should be cancelled
+        assert!(first.is_cancelled());
+        assert!(!second.is_cancelled());
+
+        // And: Unsubscribing cancels the remaining one
+        subscriptions.remove("gdocs://document/abc");
+        assert!(second.is_cancelled());
+    }
+
+    #[test]
+    fn clones_share_subscriptions() {
+        // Given: A subscription and a clone of its set
+        let subscriptions = Subscriptions::default();
+        let token = CancellationToken::new();
+        subscriptions.insert("gdocs://document/abc", token.clone());
+        let clone = subscriptions.clone();
+
+        // When: Unsubscribing through the clone
+        clone.remove("gdocs://document/abc");
+
+        // Then: The original's task should be stopped too
+        assert!(token.is_cancelled());
+    }
+}
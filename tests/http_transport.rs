@@ -0,0 +1,203 @@
+use google_docs_mcp_server::http::{self, HttpAuth, JwtAuth};
+use google_docs_mcp_server::{GoogleDocsClient, GoogleDocsMcpServer};
+use rmcp::ServiceExt;
+use rmcp::model::{ArgumentInfo, CompleteRequestParam, Reference};
+use rmcp::transport::StreamableHttpClientTransport;
+use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+
+const TOKEN: &str = "test-token";
+
+/// Start the HTTP server on an ephemeral localhost port
+async fn start_server(
+    auth: HttpAuth,
+) -> (SocketAddr, CancellationToken, tokio::task::JoinHandle<()>) {
+    let client = GoogleDocsClient::from_json_file("tests/fixtures/service-account.json").unwrap();
+    let server = GoogleDocsMcpServer::new(client);
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let shutdown = CancellationToken::new();
+    let handle = tokio::spawn({
+        let shutdown = shutdown.clone();
+        async move {
+            http::serve(listener, server, auth, shutdown).await.unwrap();
+        }
+    });
+
+    (addr, shutdown, handle)
+}
+
+fn endpoint(addr: SocketAddr) -> String {
+    format!("http://{}{}", addr, http::MCP_PATH)
+}
+
+/// Connect an rmcp client that sends `token` as its bearer credential
+async fn connect(
+    addr: SocketAddr,
+    token: &str,
+) -> rmcp::service::RunningService<rmcp::RoleClient, ()> {
+    let mut headers = reqwest::header::HeaderMap::new();
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    let http_client = reqwest::Client::builder()
+        .default_headers(headers)
+        .build()
+        .unwrap();
+    let transport = StreamableHttpClientTransport::with_client(
+        http_client,
+        StreamableHttpClientTransportConfig::with_uri(endpoint(addr)),
+    );
+    ().serve(transport).await.unwrap()
+}
+
+#[tokio::test]
+async fn client_with_bearer_token_lists_tools() {
+    // Given: A server requiring a static bearer token
+    let (addr, shutdown, handle) = start_server(HttpAuth::BearerToken(TOKEN.to_string())).await;
+
+    // When: An rmcp client connects with the token and lists tools
+    let client = connect(addr, TOKEN).await;
+    let tools = client.list_all_tools().await.unwrap();
+
+    // Then: The document tools should be available
+    let names: Vec<&str> = tools.iter().map(|t| t.name.as_ref()).collect();
+    assert!(names.contains(&"google_docs_get_document"));
+    assert!(names.contains(&"google_docs_update_document"));
+
+    // And: Every tool should declare its structured output
+    let get_document = tools
+        .iter()
+        .find(|t| t.name == "google_docs_get_document")
+        .unwrap();
+    let schema = get_document.output_schema.as_ref().unwrap();
+    assert!(schema["properties"]["tabs"].is_object());
+    let without_schema: Vec<&str> = tools
+        .iter()
+        .filter(|t| t.output_schema.is_none())
+        .map(|t| t.name.as_ref())
+        .collect();
+    assert!(without_schema.is_empty(), "{:?}", without_schema);
+
+    client.cancel().await.unwrap();
+    shutdown.cancel();
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn client_lists_document_resource_templates() {
+    // Given: A server without the Drive search feature
+    let (addr, shutdown, handle) = start_server(HttpAuth::BearerToken(TOKEN.to_string())).await;
+
+    // When: A client lists resources and resource templates
+    let client = connect(addr, TOKEN).await;
+    let resources = client.list_all_resources().await.unwrap();
+    let templates = client.list_all_resource_templates().await.unwrap();
+
+    // Then: No documents have been fetched yet, but the templates are offered
+    assert!(resources.is_empty());
+    let uris: Vec<&str> = templates.iter().map(|t| t.uri_template.as_str()).collect();
+    assert_eq!(
+        uris,
+        [
+            "gdocs://document/{documentId}",
+            "gdocs://document/{documentId}/tab/{tabId}"
+        ]
+    );
+
+    client.cancel().await.unwrap();
+    shutdown.cancel();
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn client_completes_document_ids() {
+    // Given: A server that has not fetched any documents yet
+    let (addr, shutdown, handle) = start_server(HttpAuth::BearerToken(TOKEN.to_string())).await;
+
+    // When: A client asks for document ID completions for a prompt
+    let client = connect(addr, TOKEN).await;
+    let result = client
+        .complete(CompleteRequestParam {
+            r#ref: Reference::for_prompt("summarize_document"),
+            argument: ArgumentInfo {
+                name: "document_id".to_string(),
+                value: String::new(),
+            },
+            context: None,
+        })
+        .await
+        .unwrap();
+
+    // Then: Completions are supported, with nothing to suggest yet
+    let capabilities = client.peer_info().unwrap().capabilities.clone();
+    assert!(capabilities.completions.is_some());
+    assert!(result.completion.values.is_empty());
+
+    client.cancel().await.unwrap();
+    shutdown.cancel();
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn request_without_token_is_rejected() {
+    // Given: A server requiring a static bearer token
+    let (addr, shutdown, handle) = start_server(HttpAuth::BearerToken(TOKEN.to_string())).await;
+
+    // When: A request arrives without credentials, or with the wrong token
+    let missing = reqwest::Client::new()
+        .post(endpoint(addr))
+        .header("Accept", "application/json, text/event-stream")
+        .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+        .send()
+        .await
+        .unwrap();
+    let wrong = reqwest::Client::new()
+        .post(endpoint(addr))
+        .bearer_auth("not-the-token")
+        .json(&serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "ping"}))
+        .send()
+        .await
+        .unwrap();
+
+    // Then: Both should be refused with a bearer challenge
+    assert_eq!(missing.status(), reqwest::StatusCode::UNAUTHORIZED);
+    assert_eq!(missing.headers()["www-authenticate"], "Bearer");
+    assert_eq!(wrong.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+    shutdown.cancel();
+    handle.await.unwrap();
+}
+
+#[tokio::test]
+async fn client_with_signed_jwt_is_accepted() {
+    // Given: A server verifying HS256 client JWTs
+    let secret = b"jwt-test-secret";
+    let (addr, shutdown, handle) =
+        start_server(HttpAuth::Jwt(Arc::new(JwtAuth::hs256(secret)))).await;
+
+    // When: A client presents a JWT signed with the shared secret
+    let claims = serde_json::json!({
+        "sub": "alice@example.com",
+        "exp": chrono::Utc::now().timestamp() + 300,
+    });
+    let token = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(secret),
+    )
+    .unwrap();
+    let client = connect(addr, &token).await;
+    let tools = client.list_all_tools().await.unwrap();
+
+    // Then: The session should work
+    assert!(!tools.is_empty());
+
+    client.cancel().await.unwrap();
+    shutdown.cancel();
+    handle.await.unwrap();
+}